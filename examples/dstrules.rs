@@ -2,7 +2,6 @@ extern crate time;
 extern crate zoneinfo;
 
 use zoneinfo::ZoneInfo;
-use std::error::Error;
 
 fn main() {
     let regions = ZoneInfo::get_tz_locations();
@@ -13,7 +12,7 @@ fn main() {
                 println!("{}: {}", region, zoneinfo.get_dst_specifier());
             },
             Err(error) => {
-                println!("{}: unable to parse: {}", region, error.description());
+                println!("{}: unable to parse: {}", region, error);
             }
         }
     }
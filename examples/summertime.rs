@@ -4,11 +4,14 @@ extern crate zoneinfo;
 use zoneinfo::ZoneInfo;
 
 fn main() {
-    // Yes, in some regions this example will crash, when is no such a thing as daylight saving
-    // time
     let info = ZoneInfo::get_local_zoneinfo().unwrap();
     let now = time::now_utc().to_timespec();
 
+    if !info.has_dst() {
+        println!("There is no such a thing as daylight saving time here");
+        return;
+    }
+
     let actual = info.get_actual_zoneinfo(now).unwrap();
 
     // A very Northern/Mid-europe based example ;-)
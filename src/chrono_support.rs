@@ -0,0 +1,159 @@
+// Implementation of chrono::TimeZone for zone info, enabled by the `chrono`
+// feature. The trait is implemented for references so the offsets stored in
+// chrono's date and time types can point back to the zone cheaply.
+use std::fmt;
+use chrono::{self, FixedOffset, NaiveDate, NaiveDateTime, Offset, TimeZone};
+use time::Timespec;
+use {LocalResult, LocalTimestamp, ZoneInfo};
+
+/// Offset of a `chrono::DateTime` in a zone, see the `chrono::TimeZone`
+/// implementation of `&ZoneInfo`
+#[derive(Copy, Clone)]
+pub struct ZoneInfoOffset<'a> {
+    zone: &'a ZoneInfo,
+    offset: FixedOffset,
+    abbreviation: &'a str
+}
+
+impl<'a> ZoneInfoOffset<'a> {
+    /// returns the offset in effect at the provided moment; before the first
+    /// transition the offset of `pretransition_type` is used, like
+    /// `snapshot_offset_around` does
+    fn at(zone: &'a ZoneInfo, timestamp: Timespec) -> ZoneInfoOffset<'a> {
+        let (ut_offset, abbreviation) = match (zone.get_offset_at(timestamp), zone.get_abbreviation_at(timestamp)) {
+            (Some(ut_offset), Some(abbreviation)) => (ut_offset, abbreviation),
+            _ => match zone.pretransition_index() {
+                Some(index) => {
+                    let info = &zone.zone_info.local_times[index];
+                    (info.ut_offset, info.abbreviation.as_str())
+                },
+                None => (0, "")
+            }
+        };
+
+        ZoneInfoOffset {
+            zone,
+            // chrono only represents offsets of less than a day
+            offset: FixedOffset::east_opt(ut_offset).unwrap_or_else(|| chrono::Utc.fix()),
+            abbreviation
+        }
+    }
+
+    /// Returns the abbreviation of the time zone (like `CEST`)
+    pub fn abbreviation(&self) -> &'a str {
+        self.abbreviation
+    }
+}
+
+impl<'a> Offset for ZoneInfoOffset<'a> {
+    fn fix(&self) -> FixedOffset {
+        self.offset
+    }
+}
+
+impl<'a> fmt::Debug for ZoneInfoOffset<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{:?}", self.abbreviation, self.offset)
+    }
+}
+
+impl<'a> fmt::Display for ZoneInfoOffset<'a> {
+    /// Shows the abbreviation, or the offset itself when it has none
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.abbreviation.is_empty() {
+            write!(f, "{}", self.offset)
+        }
+        else {
+            write!(f, "{}", self.abbreviation)
+        }
+    }
+}
+
+/// The offsets of UTC moments come from `get_offset_at`, local times are
+/// resolved by `local_to_utc`, so local times skipped by the clock map to
+/// `chrono::LocalResult::None`.
+///
+/// ```rust
+/// extern crate chrono;
+/// extern crate zoneinfo;
+///
+/// use chrono::{NaiveDate, TimeZone};
+/// use zoneinfo::ZoneInfo;
+///
+/// fn main() {
+///     let zone = ZoneInfo::by_tz("Europe/Amsterdam").unwrap();
+///     let noon = NaiveDate::from_ymd_opt(2020, 7, 1).unwrap().and_hms_opt(12, 0, 0).unwrap();
+///
+///     // 2020-07-01 14:00:00 CEST
+///     println!("{}", (&zone).from_utc_datetime(&noon));
+/// }
+/// ```
+impl<'a> TimeZone for &'a ZoneInfo {
+    type Offset = ZoneInfoOffset<'a>;
+
+    fn from_offset(offset: &ZoneInfoOffset<'a>) -> &'a ZoneInfo {
+        offset.zone
+    }
+
+    fn offset_from_local_date(&self, local: &NaiveDate) -> chrono::LocalResult<ZoneInfoOffset<'a>> {
+        self.offset_from_local_datetime(&local.and_time(Default::default()))
+    }
+
+    fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> chrono::LocalResult<ZoneInfoOffset<'a>> {
+        let zone: &'a ZoneInfo = self;
+
+        match zone.local_to_utc(LocalTimestamp(timespec(local))) {
+            LocalResult::None => chrono::LocalResult::None,
+            LocalResult::Single(utc) => chrono::LocalResult::Single(ZoneInfoOffset::at(zone, utc)),
+            LocalResult::Ambiguous(earlier, later) => {
+                chrono::LocalResult::Ambiguous(ZoneInfoOffset::at(zone, earlier), ZoneInfoOffset::at(zone, later))
+            }
+        }
+    }
+
+    fn offset_from_utc_date(&self, utc: &NaiveDate) -> ZoneInfoOffset<'a> {
+        self.offset_from_utc_datetime(&utc.and_time(Default::default()))
+    }
+
+    fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> ZoneInfoOffset<'a> {
+        ZoneInfoOffset::at(self, timespec(utc))
+    }
+}
+
+/// returns the provided date and time as if it was UTC
+fn timespec(datetime: &NaiveDateTime) -> Timespec {
+    let utc = datetime.and_utc();
+
+    Timespec::new(utc.timestamp(), utc.timestamp_subsec_nanos() as i32)
+}
+
+#[test]
+fn chrono_time_zone() {
+    let zone = ZoneInfo::by_tz("Europe/Amsterdam").unwrap();
+    let date = |year, month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+    let datetime = |year, month, day, hour, min| date(year, month, day).and_hms_opt(hour, min, 0).unwrap();
+
+    let summer = (&zone).from_utc_datetime(&datetime(2020, 7, 1, 12, 0));
+    assert_eq!(summer.offset().fix().local_minus_utc(), 7200);
+    assert_eq!(summer.to_string(), "2020-07-01 14:00:00 CEST");
+    assert_eq!((&zone).offset_from_utc_date(&date(2020, 1, 1)).abbreviation(), "CET");
+
+    // arithmetic keeps following the zone
+    let winter = summer + chrono::Duration::days(180);
+    assert_eq!(winter.offset().fix().local_minus_utc(), 3600);
+
+    // beyond the last listed transition the POSIX TZ rule is followed
+    assert_eq!((&zone).offset_from_utc_datetime(&datetime(2050, 7, 1, 12, 0)).abbreviation(), "CEST");
+
+    let local = (&zone).from_local_datetime(&datetime(2020, 7, 1, 14, 0)).unwrap();
+    assert_eq!(local.naive_utc(), datetime(2020, 7, 1, 12, 0));
+    assert_eq!((&zone).from_local_datetime(&datetime(2020, 3, 29, 2, 30)), chrono::LocalResult::None);
+    match (&zone).from_local_datetime(&datetime(2020, 10, 25, 2, 30)) {
+        chrono::LocalResult::Ambiguous(earlier, later) => {
+            assert_eq!(earlier.offset().fix().local_minus_utc(), 7200);
+            assert_eq!(later.offset().fix().local_minus_utc(), 3600);
+        },
+        _ => panic!("repeated local time not ambiguous")
+    }
+    assert!((&zone).offset_from_local_date(&date(2020, 3, 29)).single().is_some());
+}
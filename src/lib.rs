@@ -6,20 +6,41 @@
 
 extern crate byteorder;
 extern crate time;
+#[cfg(feature = "chrono")]
+extern crate chrono;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_json;
 
 mod visitdir;
+mod civil;
+mod posix;
+#[cfg(feature = "chrono")]
+mod chrono_support;
+#[cfg(test)]
+mod testdata;
 
 use std::fs::{File, metadata};
 use std::path::{Path, PathBuf};
 use std::io::{Read, Cursor};
 use byteorder::{BigEndian, ReadBytesExt};
-use time::Timespec;
+use time::{Duration, Timespec};
 use std::collections::BTreeMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
+use std::sync::OnceLock;
+use std::fmt;
+
+pub use posix::{PosixTz, PosixDst, PosixRule, PosixDate};
+#[cfg(feature = "chrono")]
+pub use chrono_support::ZoneInfoOffset;
 
 // format is described in timezone/tzfile.h of the GNU libc library
 #[derive(Debug, Clone)]
 struct TzHeadInner {
-    tzh_magic: String, // TZ_MAGIC
+    tzh_magic: [u8; 4], // TZ_MAGIC
     tzh_version: char, // '\0' or '2' or '3' as of 2013
     // 15 bytes (reserved; must be zero)
     tzh_ttigmtcnt: u32, // coded number of trans. time flags
@@ -28,6 +49,55 @@ struct TzHeadInner {
     tzh_timecnt: u32, // coded number of transition times
     tzh_typecnt: u32, // coded number of local time types
     tzh_charcnt: u32, // coded number of abbr. chars
+    time_size: u64, // not coded; 4 for the version 1 block, 8 for the version 2+ block
+}
+
+impl TzHeadInner {
+    /// returns the size in bytes of the data block following this header
+    ///
+    /// the counts are taken from the file as is, so a crafted header could
+    /// announce more data than fits in a `u64`; such headers are rejected.
+    fn data_block_len(&self) -> Result<u64, ZoneInfoError> {
+        let time_size = self.time_size;
+        let sizes = [
+            (self.tzh_timecnt as u64).checked_mul(time_size + 1),
+            (self.tzh_typecnt as u64).checked_mul(6),
+            Some(self.tzh_charcnt as u64),
+            (self.tzh_leapcnt as u64).checked_mul(time_size + 4),
+            Some(self.tzh_ttisstdcnt as u64),
+            Some(self.tzh_ttigmtcnt as u64),
+        ];
+
+        sizes.iter().try_fold(0u64, |total, &size| size.and_then(|size| total.checked_add(size)))
+             .ok_or_else(|| ZoneInfoError::Malformed("header counts exceed the addressable size".to_string()))
+    }
+
+    /// returns the version and counts of this header
+    fn zone_header(&self) -> ZoneHeader {
+        ZoneHeader {
+            version: self.tzh_version as u8,
+            isut_count: self.tzh_ttigmtcnt,
+            isstd_count: self.tzh_ttisstdcnt,
+            leap_count: self.tzh_leapcnt,
+            transition_count: self.tzh_timecnt,
+            type_count: self.tzh_typecnt,
+            char_count: self.tzh_charcnt,
+        }
+    }
+
+    /// returns the size in bytes of the header and each table of the data block
+    fn section_sizes(&self) -> SectionSizes {
+        let time_size = self.time_size as usize;
+
+        SectionSizes {
+            header: 44,
+            transitions: self.tzh_timecnt as usize * (time_size + 1),
+            local_time_types: self.tzh_typecnt as usize * 6,
+            abbreviations: self.tzh_charcnt as usize,
+            leap_seconds: self.tzh_leapcnt as usize * (time_size + 4),
+            transition_flags: self.tzh_ttisstdcnt as usize + self.tzh_ttigmtcnt as usize,
+        }
+    }
 }
 
 struct TzHead<F: Fn(&mut Cursor<&[u8]>)->Result<i64, std::io::Error>> {
@@ -43,6 +113,7 @@ struct TzType {
 }
 
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Attributes associated to transition
 pub enum TransitionTimeFlag {
     Standard,
@@ -51,24 +122,123 @@ pub enum TransitionTimeFlag {
     Local
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+/// Origin of the zone information for a particular moment
+pub enum DateSource {
+    /// Covered by the transitions listed in the zoneinfo file
+    Table,
+    /// Beyond the last listed transition, governed by the POSIX TZ rule
+    PosixRule
+}
+
+/// Version of the `tzfile(5)` format, see `ZoneInfo::version`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TzVersion {
+    /// Only 32-bit data, no POSIX TZ string
+    V1,
+    /// Adds 64-bit data and a POSIX TZ string
+    V2,
+    /// Allows POSIX TZ string extensions (negative and 24+ hour rule times)
+    V3,
+    /// Allows leap second tables not starting at a multiple of a second
+    V4
+}
+
+/// Errors which can occur while loading zone info
+#[derive(Debug)]
+pub enum ZoneInfoError {
+    /// Reading the zoneinfo file failed, or the location doesn't exist
+    /// (`std::io::ErrorKind::NotFound`)
+    Io(std::io::Error),
+    /// The data doesn't start with the `TZif` magic bytes
+    InvalidMagic,
+    /// The data ends before everything announced by the header was read
+    Truncated,
+    /// A time zone abbreviation or the POSIX TZ string isn't valid UTF-8
+    BadUtf8(std::str::Utf8Error),
+    /// The version byte of the header is not a known version
+    UnsupportedVersion(char),
+    /// The file exceeds `ParseOptions::max_bytes`
+    FileTooLarge,
+    /// Something follows the POSIX TZ string at the end of the file
+    TrailingData,
+    /// The tables of the data block contradict each other or the format,
+    /// like duplicate or unordered transition times
+    Malformed(String),
+    /// The POSIX TZ string is malformed
+    InvalidPosixTz(String),
+    /// The range of years to compute transitions for is empty
+    EmptyYearRange(Range<i32>),
+}
+
+impl fmt::Display for ZoneInfoError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ZoneInfoError::Io(ref err) => write!(f, "{}", err),
+            ZoneInfoError::InvalidMagic => write!(f, "not a zoneinfo file (invalid magic)"),
+            ZoneInfoError::Truncated => write!(f, "zoneinfo data is truncated"),
+            ZoneInfoError::BadUtf8(ref err) => write!(f, "zoneinfo text is not valid UTF-8: {}", err),
+            ZoneInfoError::UnsupportedVersion(version) => {
+                write!(f, "unsupported zoneinfo version {:?}", version)
+            },
+            ZoneInfoError::FileTooLarge => write!(f, "zoneinfo file exceeds the size limit"),
+            ZoneInfoError::TrailingData => write!(f, "trailing data after the POSIX TZ string"),
+            ZoneInfoError::Malformed(ref message) => write!(f, "malformed zoneinfo data: {}", message),
+            ZoneInfoError::InvalidPosixTz(ref message) => write!(f, "invalid POSIX TZ string: {}", message),
+            ZoneInfoError::EmptyYearRange(ref years) => {
+                write!(f, "empty range of years {}..{}", years.start, years.end)
+            },
+        }
+    }
+}
+
+impl std::error::Error for ZoneInfoError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match *self {
+            ZoneInfoError::Io(ref err) => Some(err),
+            ZoneInfoError::BadUtf8(ref err) => Some(err),
+            _ => None
+        }
+    }
+}
+
+impl From<std::io::Error> for ZoneInfoError {
+    /// Running out of data while decoding (`std::io::ErrorKind::UnexpectedEof`)
+    /// becomes `ZoneInfoError::Truncated`, other errors are kept as is
+    fn from(err: std::io::Error) -> ZoneInfoError {
+        if err.kind() == std::io::ErrorKind::UnexpectedEof {
+            ZoneInfoError::Truncated
+        }
+        else {
+            ZoneInfoError::Io(err)
+        }
+    }
+}
+
 impl <F: Fn(&mut Cursor<&[u8]>)->Result<i64, std::io::Error>>TzHead<F> {
     /// returns parsed zoneinfo header
-    fn new(reader: &mut Cursor<&[u8]>, x: F) -> Result<TzHead<F>, std::io::Error> {
+    fn new(reader: &mut Cursor<&[u8]>, time_size: u64, x: F) -> Result<TzHead<F>, ZoneInfoError> {
         let mut magic:[u8; 4] = [0;4];
-        try!(reader.read(&mut magic));
-        let version = try!(reader.read_u8());
+        reader.read_exact(&mut magic)?;
+        if &magic != b"TZif" {
+            return Err(ZoneInfoError::InvalidMagic);
+        }
+        let version = reader.read_u8()?;
+        if version != 0 && !(b'2'..=b'4').contains(&version) {
+            return Err(ZoneInfoError::UnsupportedVersion(version as char));
+        }
         let position = reader.position();
         reader.set_position(position + 15); // skip reserved bytes
-        let ttigmtcnt = try!(reader.read_u32::<BigEndian>());
-        let ttisstdcnt = try!(reader.read_u32::<BigEndian>());
-        let leapcnt = try!(reader.read_u32::<BigEndian>());
-        let timecnt = try!(reader.read_u32::<BigEndian>());
-        let typecnt = try!(reader.read_u32::<BigEndian>());
-        let charcnt = try!(reader.read_u32::<BigEndian>());
+        let ttigmtcnt = reader.read_u32::<BigEndian>()?;
+        let ttisstdcnt = reader.read_u32::<BigEndian>()?;
+        let leapcnt = reader.read_u32::<BigEndian>()?;
+        let timecnt = reader.read_u32::<BigEndian>()?;
+        let typecnt = reader.read_u32::<BigEndian>()?;
+        let charcnt = reader.read_u32::<BigEndian>()?;
 
         Ok(TzHead {
             inner: TzHeadInner {
-                tzh_magic: std::str::from_utf8(&magic).unwrap().to_string(), // FIXME: remove unwrap
+                tzh_magic: magic,
                 tzh_version: version as char,
                 tzh_ttigmtcnt: ttigmtcnt,
                 tzh_ttisstdcnt: ttisstdcnt,
@@ -76,6 +246,7 @@ impl <F: Fn(&mut Cursor<&[u8]>)->Result<i64, std::io::Error>>TzHead<F> {
                 tzh_timecnt: timecnt,
                 tzh_typecnt: typecnt,
                 tzh_charcnt: charcnt,
+                time_size,
             },
             time_consumer: x
         })
@@ -85,11 +256,22 @@ impl <F: Fn(&mut Cursor<&[u8]>)->Result<i64, std::io::Error>>TzHead<F> {
     ///
     /// the function assumes that the provided cursor is located at the the start of the
     /// table with transition times.
-    fn decode_transition_times(&self, reader: &mut Cursor<&[u8]>) -> Result<Vec<Timespec>, std::io::Error> {
+    ///
+    /// two transitions at the same time would make one of them disappear from
+    /// the transition map, so such (malformed) tables are rejected.
+    fn decode_transition_times(&self, reader: &mut Cursor<&[u8]>) -> Result<Vec<Timespec>, ZoneInfoError> {
         let mut transition_times = Vec::<Timespec>::new();
 
         for _ in 0..self.inner.tzh_timecnt {
-            transition_times.push(Timespec::new(try!((self.time_consumer)(reader)), 0));
+            let time = Timespec::new((self.time_consumer)(reader)?, 0);
+            if transition_times.last() == Some(&time) {
+                return Err(ZoneInfoError::Malformed("duplicate transition time".to_string()));
+            }
+            // lookups rely on binary search
+            if transition_times.last().is_some_and(|&last| last > time) {
+                return Err(ZoneInfoError::Malformed("transition times not in ascending order".to_string()));
+            }
+            transition_times.push(time);
         }
 
         Ok(transition_times)
@@ -99,11 +281,20 @@ impl <F: Fn(&mut Cursor<&[u8]>)->Result<i64, std::io::Error>>TzHead<F> {
     ///
     /// the function assumes that the provided cursor is located at the the start of the
     /// table with transition types.
-    fn decode_transition_types(&self, reader: &mut Cursor<&[u8]>) -> Result<Vec<u8>, std::io::Error> {
+    ///
+    /// the lookups index the local time types with these, so types beyond the
+    /// number of local time types are rejected.
+    fn decode_transition_types(&self, reader: &mut Cursor<&[u8]>) -> Result<Vec<u8>, ZoneInfoError> {
         let mut transition_types = Vec::<u8>::new();
 
-        for _ in 0..self.inner.tzh_timecnt {
-            transition_types.push(try!(reader.read_u8()));
+        for transition in 0..self.inner.tzh_timecnt {
+            let type_index = reader.read_u8()?;
+            if type_index as u32 >= self.inner.tzh_typecnt {
+                return Err(ZoneInfoError::Malformed(format!(
+                    "local time type {} of transition {} exceeds the {} local time types",
+                    type_index, transition, self.inner.tzh_typecnt)));
+            }
+            transition_types.push(type_index);
         }
 
         Ok(transition_types)
@@ -115,50 +306,63 @@ impl <F: Fn(&mut Cursor<&[u8]>)->Result<i64, std::io::Error>>TzHead<F> {
     ///
     /// the function assumes that the provided cursor is located at the the start of the
     /// table with local time startings data
-    fn decode_local_time_data(&self, reader: &mut Cursor<&[u8]>) -> Result<Vec<TzType>, std::io::Error> {
+    ///
+    /// abbreviations which aren't valid UTF-8 are rejected, unless `lossy` is
+    /// set; then invalid sequences are replaced by U+FFFD
+    fn decode_local_time_data(&self, reader: &mut Cursor<&[u8]>, lossy: bool)
+                              -> Result<(Vec<TzType>, Vec<u8>), ZoneInfoError> {
         let mut local_time_data = Vec::<TzType>::new();
         let mut raw_local_time_data = vec![];
 
         for _ in 0..self.inner.tzh_typecnt {
-            let ut_offset = try!(reader.read_i32::<BigEndian>());
-            let isdst = try!(reader.read_u8());
-            let abbr_index = try!(reader.read_u8());
+            let ut_offset = reader.read_i32::<BigEndian>()?;
+            let isdst = reader.read_u8()?;
+            let abbr_index = reader.read_u8()?;
 
             raw_local_time_data.push((ut_offset, isdst, abbr_index));
         }
 
         let mut charbuf = vec![0u8; self.inner.tzh_charcnt as usize];
-        try!(reader.read(&mut charbuf[..]));
+        reader.read_exact(&mut charbuf[..])?;
 
-        for (ut_offset, isdst, abbr_index) in raw_local_time_data {
+        for (type_index, (ut_offset, isdst, abbr_index)) in raw_local_time_data.into_iter().enumerate() {
+            if abbr_index as usize >= charbuf.len() {
+                return Err(ZoneInfoError::Malformed(format!(
+                    "abbreviation index {} of local time type {} exceeds the {} abbreviation bytes",
+                    abbr_index, type_index, charbuf.len())));
+            }
             // In C: strcpy(abbreviation, &charbuf[abbr_index]) -- also a solution possible without clone?
             let abbr:Vec<_> = charbuf.clone().into_iter()
                                      .skip(abbr_index as usize)
                                      .take_while(|&c| c > 0)
                                      .collect();
-            let abbreviation = std::str::from_utf8(&abbr[..]).unwrap(); // FIXME: improve error handling
+            let abbreviation = match std::str::from_utf8(&abbr[..]) {
+                Ok(abbreviation) => abbreviation.to_string(),
+                Err(_) if lossy => String::from_utf8_lossy(&abbr[..]).into_owned(),
+                Err(err) => return Err(ZoneInfoError::BadUtf8(err))
+            };
             local_time_data.push(TzType{
-                ut_offset: ut_offset,
+                ut_offset,
                 isdst: isdst != 0,
-                abbreviation: abbreviation.to_string(),
+                abbreviation,
             })
         }
 
-        Ok(local_time_data)
+        Ok((local_time_data, charbuf))
     }
 
     /// returns a list of leap seconds transition changes
     ///
     /// the function assumes that the provided cursor is located at the the start of the
     /// table with leap second transitions
-    fn decode_leap_second_corrections(&self, reader: &mut Cursor<&[u8]>) -> Result< Vec<(Timespec, i32)>, std::io::Error> {
+    fn decode_leap_second_corrections(&self, reader: &mut Cursor<&[u8]>) -> Result< Vec<(Timespec, i32)>, ZoneInfoError> {
         let mut leap_second_corrections = vec![];
 
         for _ in 0..self.inner.tzh_leapcnt {
-            let transition_time = try!((self.time_consumer)(reader));
-            let seconds = try!(reader.read_i32::<BigEndian>());
+            let transition_time = (self.time_consumer)(reader)?;
+            let seconds = reader.read_i32::<BigEndian>()?;
 
-            leap_second_corrections.push((Timespec::new(transition_time as i64, 0),
+            leap_second_corrections.push((Timespec::new(transition_time, 0),
                                           seconds));
         }
 
@@ -169,11 +373,11 @@ impl <F: Fn(&mut Cursor<&[u8]>)->Result<i64, std::io::Error>>TzHead<F> {
     ///
     /// the function assumes that the provided cursor is located at the the start of the
     /// table with wall clock or standard transition moments
-    fn decode_transition_flags1(&self, reader: &mut Cursor<&[u8]>) -> Result< Vec<TransitionTimeFlag>, std::io::Error> {
+    fn decode_transition_flags1(&self, reader: &mut Cursor<&[u8]>) -> Result< Vec<TransitionTimeFlag>, ZoneInfoError> {
         let mut transition_flags = vec![];
 
         for _ in 0..self.inner.tzh_ttisstdcnt {
-            transition_flags.push(match try!(reader.read_u8()) {
+            transition_flags.push(match reader.read_u8()? {
                 0 => TransitionTimeFlag::WallClock,
                 _ => TransitionTimeFlag::Standard,
             })
@@ -186,11 +390,11 @@ impl <F: Fn(&mut Cursor<&[u8]>)->Result<i64, std::io::Error>>TzHead<F> {
     ///
     /// the function assumes that the provided cursor is located at the the start of the
     /// table with local or universal transition moments
-    fn decode_transition_flags2(&self, reader: &mut Cursor<&[u8]>) -> Result< Vec<TransitionTimeFlag>, std::io::Error> {
+    fn decode_transition_flags2(&self, reader: &mut Cursor<&[u8]>) -> Result< Vec<TransitionTimeFlag>, ZoneInfoError> {
         let mut transition_flags = vec![];
 
         for _ in 0..self.inner.tzh_ttigmtcnt {
-            transition_flags.push(match try!(reader.read_u8()) {
+            transition_flags.push(match reader.read_u8()? {
                 0 => TransitionTimeFlag::Local,
                 _ => TransitionTimeFlag::Universal,
             })
@@ -200,48 +404,299 @@ impl <F: Fn(&mut Cursor<&[u8]>)->Result<i64, std::io::Error>>TzHead<F> {
     }
 }
 
+type LeapSeconds = Vec<(Timespec, i32)>;
+
 #[derive(Clone)]
 struct ZoneInfoInner {
     header: TzHeadInner,
     transision_times: Vec<Timespec>,
     transision_types: Vec<u8>,
     local_times: Vec<TzType>,
-    leap_seconds_data: Vec<(Timespec, i32)>,
+    abbreviation_chars: Vec<u8>,
+    leap_seconds_data: LeapSeconds,
     transition_flags1: Vec<TransitionTimeFlag>,
     transition_flags2: Vec<TransitionTimeFlag>
 }
 
 fn read_zone_info<F: Fn(&mut Cursor<&[u8]>)->Result<i64, std::io::Error>>
-            (cursor: &mut Cursor<&[u8]>, x: F) -> Result<ZoneInfoInner, std::io::Error> {
-    let header = try!(TzHead::new(cursor, x));
-    let mut transition_times = try!(header.decode_transition_times(cursor));
-    let mut transition_types = try!(header.decode_transition_types(cursor));
-    let local_times = try!(header.decode_local_time_data(cursor));
-    let leap_seconds_data = try!(header.decode_leap_second_corrections(cursor));
-    let transition_flags1 = try!(header.decode_transition_flags1(cursor));
-    let transition_flags2 = try!(header.decode_transition_flags2(cursor));
-
-    // when only a single time definition exists and no single transition create a dummy
-    // transition. This to support zoneinfo files which are part of the Debian, Ubuntu, Mint
-    // distribution family.
-    if transition_times.len() == 0 && local_times.len() == 1 {
-        transition_times.push(Timespec::new(std::i64::MIN, 0));
-        transition_types.push(0);
+            (cursor: &mut Cursor<&[u8]>, time_size: u64, x: F, options: &ParseOptions)
+            -> Result<ZoneInfoInner, ZoneInfoError> {
+    let header = TzHead::new(cursor, time_size, x)?;
+    read_zone_data(cursor, header, options)
+}
+
+/// decodes the data block which follows an already parsed header
+fn read_zone_data<F: Fn(&mut Cursor<&[u8]>)->Result<i64, std::io::Error>>
+            (cursor: &mut Cursor<&[u8]>, header: TzHead<F>, options: &ParseOptions)
+            -> Result<ZoneInfoInner, ZoneInfoError> {
+    let mut transition_times = header.decode_transition_times(cursor)?;
+    let mut transition_types = header.decode_transition_types(cursor)?;
+    let (local_times, abbreviation_chars) = header.decode_local_time_data(cursor, options.lossy_abbreviations)?;
+    let leap_seconds_data = header.decode_leap_second_corrections(cursor)?;
+    let transition_flags1 = header.decode_transition_flags1(cursor)?;
+    let transition_flags2 = header.decode_transition_flags2(cursor)?;
+
+    if local_times.is_empty() {
+        return Err(ZoneInfoError::Malformed("no local time types".to_string()));
+    }
+
+    // when no single transition exists create a dummy transition to the first
+    // standard time definition (usually the only one). This to support zoneinfo
+    // files which are part of the Debian, Ubuntu, Mint distribution family.
+    if transition_times.is_empty() {
+        let index = local_times.iter().position(|t| !t.isdst).unwrap_or(0);
+        transition_times.push(Timespec::new(i64::MIN, 0));
+        transition_types.push(index as u8);
     }
 
     Ok(ZoneInfoInner {
         header: header.inner,
         transision_times: transition_times,
         transision_types: transition_types,
-        local_times: local_times,
-        leap_seconds_data: leap_seconds_data,
-        transition_flags1: transition_flags1,
-        transition_flags2: transition_flags2
+        local_times,
+        abbreviation_chars,
+        leap_seconds_data,
+        transition_flags1,
+        transition_flags2
+    })
+}
+
+/// returns the POSIX TZ string following the 64-bit data block; the string is
+/// enclosed in newlines and nothing may follow it
+fn read_tail(reader: &mut Cursor<&[u8]>) -> Result<String, ZoneInfoError> {
+    let invalid = |message: &str| ZoneInfoError::Malformed(message.to_string());
+    let mut rest = Vec::<u8>::new();
+    reader.read_to_end(&mut rest)?;
+
+    if rest.first() != Some(&b'\n') {
+        return Err(invalid("POSIX TZ string not preceded by a newline"));
+    }
+
+    let end = rest[1..].iter()
+                       .position(|&c| c == b'\n')
+                       .ok_or_else(|| invalid("POSIX TZ string not followed by a newline"))? + 1;
+    if end + 1 != rest.len() {
+        return Err(ZoneInfoError::TrailingData);
+    }
+
+    String::from_utf8(rest[1..end].to_vec()).map_err(|err| ZoneInfoError::BadUtf8(err.utf8_error()))
+}
+
+/// metadata files of tzdata, which are installed next to the zoneinfo files
+const METADATA_FILES: &[&str] = &["+VERSION", "SECURITY", "iso3166.tab", "leap-seconds.list",
+                                  "leapseconds", "tzdata.zi", "zone.tab", "zone1970.tab", "zonenow.tab"];
+
+/// returns whether the provided location (a path relative to the zoneinfo
+/// directory) is a zone of its own: not part of the `posix/` or `right/`
+/// mirrors and not a metadata file
+fn is_zone_location(location: &str) -> bool {
+    !location.starts_with("posix/") && !location.starts_with("right/")
+        && !METADATA_FILES.contains(&location)
+}
+
+/// returns the location (relative to the first directory containing it) the
+/// provided location or absolute path resolves to after following symbolic
+/// links, see `ZoneInfo::canonical_name`
+fn canonical_name_in(location: &str, dirs: &[&Path]) -> Option<String> {
+    let path = if Path::new(location).is_absolute() {
+        std::fs::canonicalize(location).ok()?
+    }
+    else {
+        dirs.iter().filter_map(|dir| std::fs::canonicalize(dir.join(location)).ok()).next()?
+    };
+    if !path.is_file() {
+        return None;
+    }
+
+    dirs.iter()
+        .filter_map(|dir| std::fs::canonicalize(dir).ok())
+        .filter_map(|dir| path.strip_prefix(&dir).ok().and_then(|name| name.to_str()).map(|name| name.to_string()))
+        .next()
+}
+
+/// returns the sorted locations of all files in the provided zoneinfo directory
+fn locations_in(zoneinfo: &Path) -> Vec<String> {
+    let mut zones = vec![];
+
+    let _ = visitdir::visit_dirs(zoneinfo, &mut {|x| zones.push(x)});
+
+    let skip = zoneinfo.components().count();
+
+    let mut items = vec![];
+
+    for zone in zones {
+        let path = zone.path();
+        let without_parent = path.components().skip(skip);
+        let mut rel_path = PathBuf::new();
+
+        for part in without_parent {
+            rel_path.push(part.as_os_str());
+        }
+
+        if let Some(n) = rel_path.to_str() {
+            items.push(n.to_string());
+        }
+    }
+
+    items.sort();
+
+    items
+}
+
+/// the number of years of the POSIX TZ rule computed at most for a range of
+/// transitions, which keeps absurdly wide ranges from running out of memory
+const MAX_EXTRAPOLATED_YEARS: i64 = 10_000;
+
+/// returns the (UTC) year of the provided moment, as used to compute the
+/// transitions of a POSIX TZ rule around it
+fn posix_year(secs: i64) -> i64 {
+    let (year, _, _) = civil::civil_from_days(secs.div_euclid(86400));
+
+    year
+}
+
+/// returns the zone info of the standard or daylight saving time of a POSIX
+/// TZ rule
+fn posix_element(tz: &PosixTz, isdst: bool) -> ZoneInfoElement {
+    let (ut_offset, abbreviation) = match tz.dst {
+        Some(ref dst) if isdst => (dst.ut_offset, dst.abbreviation.clone()),
+        _ => (tz.std_ut_offset, tz.std_abbreviation.clone())
+    };
+
+    ZoneInfoElement {
+        ut_offset,
+        isdst: isdst && tz.dst.is_some(),
+        abbreviation,
+        wall_clock_or_standard: TransitionTimeFlag::WallClock,
+        local_or_universal_time: TransitionTimeFlag::Local,
+    }
+}
+
+/// returns the provided local date and time as seconds since 1970-01-01T00:00
+/// local time, or `None` when a field is out of range
+fn local_seconds(year: i64, month: u32, day: u32, hour: u32, min: u32, sec: u32) -> Option<i64> {
+    if !(1..=12).contains(&month) || day < 1 || day > civil::days_in_month(year, month) ||
+       hour > 23 || min > 59 || sec > 59 {
+        return None;
+    }
+
+    let days = civil::days_from_civil(year, month, day);
+
+    Some(days * 86400 + (hour * 3600 + min * 60 + sec) as i64)
+}
+
+/// returns the provided offset to UTC like `+0200` or, with ":" as separator,
+/// `+02:00`; seconds are only appended when there are any
+fn offset_string(offset: i32, separator: &str) -> String {
+    let sign = if offset < 0 { '-' } else { '+' };
+    let offset = offset.abs();
+    let (hours, minutes, seconds) = (offset / 3600, offset / 60 % 60, offset % 60);
+
+    if seconds == 0 {
+        format!("{}{:02}{}{:02}", sign, hours, separator, minutes)
+    }
+    else {
+        format!("{}{:02}{}{:02}{}{:02}", sign, hours, separator, minutes, separator, seconds)
+    }
+}
+
+/// returns the provided abbreviation without POSIX TZ quotes and, when it's
+/// numeric like `+03`, with the minutes written out like `+0300`
+fn normalized_abbreviation(abbreviation: &str) -> String {
+    let unquoted = abbreviation.trim_start_matches('<').trim_end_matches('>');
+    let bytes = unquoted.as_bytes();
+    let numeric = (bytes.len() == 3 || bytes.len() == 5) && (bytes[0] == b'+' || bytes[0] == b'-') &&
+                  bytes[1..].iter().all(|c| c.is_ascii_digit());
+
+    if numeric && bytes.len() == 3 {
+        format!("{}00", unquoted)
+    }
+    else {
+        unquoted.to_string()
+    }
+}
+
+/// tells whether a transition from `previous` (if known) to `info` changes
+/// the offset, daylight saving time or abbreviation
+fn is_change(previous: &Option<ZoneInfoElement>, info: &ZoneInfoElement) -> bool {
+    previous.as_ref().is_none_or(|p| {
+        p.ut_offset != info.ut_offset || p.isdst != info.isdst || p.abbreviation != info.abbreviation
     })
 }
 
+/// returns the provided string as JSON string literal
+fn json_string(s: &str) -> String {
+    let mut json = String::from("\"");
+
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c)
+        }
+    }
+    json.push('"');
+
+    json
+}
+
+/// returns the degrees of an ISO 6709 coordinate like `+4120` or `-0734717`
+/// with the provided number of degree digits
+fn iso6709_degrees(coordinate: &str, degree_digits: usize) -> Option<f64> {
+    let sign = match coordinate.as_bytes().first() {
+        Some(b'+') => 1.0,
+        Some(b'-') => -1.0,
+        _ => return None
+    };
+    let digits = &coordinate[1..];
+    if !digits.bytes().all(|c| c.is_ascii_digit()) || digits.len() < degree_digits + 2 {
+        return None;
+    }
+
+    let degrees: f64 = digits[..degree_digits].parse().ok()?;
+    let minutes: f64 = digits[degree_digits..degree_digits + 2].parse().ok()?;
+    let seconds: f64 = match digits.len() - degree_digits {
+        2 => 0.0,
+        4 => digits[degree_digits + 2..].parse().ok()?,
+        _ => return None
+    };
+
+    Some(sign * (degrees + minutes / 60.0 + seconds / 3600.0))
+}
+
+/// returns the zones described by the contents of a `zone.tab` file; lines
+/// which can't be parsed are skipped
+fn parse_zone_tab(contents: &str) -> Vec<ZoneMeta> {
+    contents.lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| {
+                let mut fields = line.split('\t');
+                let country_code = fields.next()?;
+                let coordinates = fields.next()?;
+                let location = fields.next()?;
+                let comment = fields.next().filter(|comment| !comment.is_empty());
+
+                // the latitude has 2 degree digits, the longitude 3
+                let split = coordinates.get(1..)?.find(['+', '-'])? + 1;
+                let (latitude, longitude) = coordinates.split_at(split);
+
+                Some(ZoneMeta {
+                    location: location.to_string(),
+                    country_code: country_code.to_string(),
+                    latitude: iso6709_degrees(latitude, 2)?,
+                    longitude: iso6709_degrees(longitude, 3)?,
+                    comment: comment.map(|comment| comment.to_string())
+                })
+            })
+            .collect()
+}
+
 fn consume_32bit_timestamps(reader: &mut Cursor<&[u8]>) -> Result<i64, std::io::Error> {
-    Ok(try!(reader.read_i32::<BigEndian>()) as i64)
+    Ok(reader.read_i32::<BigEndian>()? as i64)
 }
 fn consume_64bit_timestamps(reader: &mut Cursor<&[u8]>) -> Result<i64, std::io::Error> {
     reader.read_i64::<BigEndian>()
@@ -249,6 +704,7 @@ fn consume_64bit_timestamps(reader: &mut Cursor<&[u8]>) -> Result<i64, std::io::
 
 /// Transition details
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ZoneInfoElement {
     /// Offset to UTC in seconds
     pub ut_offset: i32,
@@ -262,238 +718,4536 @@ pub struct ZoneInfoElement {
     pub local_or_universal_time: TransitionTimeFlag,
 }
 
-/// Time zone information
-#[derive(Clone)]
-pub struct ZoneInfo {
-    zone_info:ZoneInfoInner,
-    time_zone_specifier:String
+/// Sizes in bytes of the parts of a zoneinfo data block
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SectionSizes {
+    /// Header preceding the data block
+    pub header: usize,
+    /// Transition times and their local time type indices
+    pub transitions: usize,
+    /// Local time type records
+    pub local_time_types: usize,
+    /// Buffer with time zone abbreviations
+    pub abbreviations: usize,
+    /// Leap second records
+    pub leap_seconds: usize,
+    /// Standard/wall clock and UT/local indicators
+    pub transition_flags: usize,
 }
 
-impl ZoneInfo {
-    /// Load zone info from a provided `tzfile(5)`. These files are often
-    /// located in `/usr/share/zoneinfo` or `/usr/local/share/info`. Depending on
-    /// your system the systems zoneinfo file is located in `/etc/localtime`.
-    pub fn new(zoneinfofile: &Path) -> Result<ZoneInfo, std::io::Error> {
-        let mut file = try!(File::open(&zoneinfofile));
-        let mut buffer = Vec::<u8>::new();
-        try!(file.read_to_end(&mut buffer));
-        let mut cursor = Cursor::new(&buffer[..]);
-        let mut tail = String::new();
+impl SectionSizes {
+    /// Returns the size of the header and data block together, saturating at
+    /// `usize::MAX`
+    pub fn total(&self) -> usize {
+        [self.transitions, self.local_time_types, self.abbreviations, self.leap_seconds,
+         self.transition_flags].iter().fold(self.header, |total, &size| total.saturating_add(size))
+    }
+}
 
-        let tz:ZoneInfoInner;
-        let b32 = try!(read_zone_info(&mut cursor, consume_32bit_timestamps));
-        if b32.header.tzh_version == '2' ||
-           b32.header.tzh_version == '3' {
-            let b64 = try!(read_zone_info(&mut cursor, consume_64bit_timestamps));
-            // during testing 64 bit variants can't be used on 32-bit systems
-            // due to different glibc2 behavior (which is used as backend format
-            // for Linux systems)
-            if cfg!(target_pointer_width = "64") {
-                tz = b64;
-            }
-            else
-            {
-                tz = b32;
-            }
-            cursor.read_to_string(&mut tail).unwrap();
-        }
-        else {
-           tz = b32;
-        }
+/// Version and counts of a zoneinfo header, see `ZoneInfo::parse_header_only`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ZoneHeader {
+    /// Version byte (`0` for version 1 files, otherwise `b'2'` and up)
+    pub version: u8,
+    /// Number of UT/local indicators
+    pub isut_count: u32,
+    /// Number of standard/wall clock indicators
+    pub isstd_count: u32,
+    /// Number of leap second records
+    pub leap_count: u32,
+    /// Number of transition times
+    pub transition_count: u32,
+    /// Number of local time types
+    pub type_count: u32,
+    /// Number of bytes of time zone abbreviations
+    pub char_count: u32,
+}
 
-        Ok(ZoneInfo{zone_info:tz, time_zone_specifier:tail})
-    }
+/// Result of mapping a local wall clock time onto UTC
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LocalResult<T> {
+    /// The local time does not exist, because the clock skipped it
+    None,
+    /// The local time maps to a single moment
+    Single(T),
+    /// The local time occurred twice, because the clock was set back; the
+    /// earliest moment comes first
+    Ambiguous(T, T)
+}
 
-    /// Load zone info based on a provided location.
-    ///
-    /// ```rust
-    /// use zoneinfo::ZoneInfo;
-    /// let info = ZoneInfo::by_tz("Europe/Amsterdam").unwrap();
-    ///
-    /// println!("Daylight saving time rules are: {}", info.get_dst_specifier());
-    /// ```
-    ///
-    /// Not available for Windows users
-    pub fn by_tz(location: &str) -> Result<ZoneInfo, std::io::Error> {
-        let all = ZoneInfo::get_tz_locations();
-        if !all.contains(&location.to_string()) {
-            return Err(std::io::Error::new(std::io::ErrorKind::NotFound,
-                "provided location not found"));
-        }
-
-        let zoneinfo;
-        let mut try_location = PathBuf::from("/usr/share/zoneinfo");
-        try_location.push(location);
-
-        // this could have be very simple whether try_location.is_file()
-        // would have be stable.
-        let meta = metadata(&try_location);
-        let try_alternative = match meta {
-            Ok(m) => !m.is_file(),
-            Err(_) => true
-        };
+/// A moment in UTC, as expected by `ZoneInfo::get_actual_zoneinfo`. Plain
+/// `Timespec` values are accepted as UTC as well.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UtcTimestamp(pub Timespec);
 
-        if try_alternative {
-            let mut try_location = PathBuf::from("/usr/local/share/zoneinfo");
-            try_location.push(location);
-            zoneinfo = try_location;
-        }
-        else {
-            zoneinfo = try_location;
-        }
+/// A local wall clock time expressed as if it was a UTC timestamp, as
+/// expected by `ZoneInfo::local_to_utc`. Plain `Timespec` values are accepted
+/// as local time as well; wrapping them makes it impossible to mix up both
+/// kinds of timestamps.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LocalTimestamp(pub Timespec);
 
-        ZoneInfo::new(&zoneinfo)
+impl From<Timespec> for UtcTimestamp {
+    fn from(timestamp: Timespec) -> UtcTimestamp {
+        UtcTimestamp(timestamp)
     }
+}
 
-    /// Retrieve local zoneinfo settings
-    ///
-    /// Not available for Windows users
-    pub fn get_local_zoneinfo() -> Result<ZoneInfo, std::io::Error> {
-        ZoneInfo::new(&Path::new("/etc/localtime"))
+impl From<Timespec> for LocalTimestamp {
+    fn from(timestamp: Timespec) -> LocalTimestamp {
+        LocalTimestamp(timestamp)
     }
+}
 
-    /// Retrieve all supported zoneinfo locations available at this machine.
-    /// These locations can be used by `ZoneInfo::by_tz`.
-    ///
-    /// Not available for Windows users
-    pub fn get_tz_locations() -> Vec<String> {
-        let mut zones = vec![];
+/// Zone info at a particular moment without heap allocations, suitable to
+/// hand over through a C ABI, see `ZoneInfo::current_state_repr`
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CurrentState {
+    /// Offset to UTC in seconds
+    pub offset: i32,
+    /// Daylight saving time
+    pub isdst: bool,
+    /// Abbreviation of the time zone, truncated to 7 bytes and padded with
+    /// NUL bytes (so always NUL terminated)
+    pub abbr: [u8; 8],
+}
 
-        let used_zoneinfo;
-        let zoneinfo = Path::new("/usr/share/zoneinfo");
+/// Offset to UTC of a zone which is only looked up again once a transition
+/// has passed, see `ZoneInfo::cached_offset`
+#[derive(Clone)]
+pub struct CachedOffset<'a> {
+    zone: &'a ZoneInfo,
+    offset: Option<i32>,
+    valid_from: Timespec,
+    valid_until: Option<Timespec>
+}
 
-        let _ = visitdir::visit_dirs(zoneinfo, &mut {|x| zones.push(x)});
+impl<'a> CachedOffset<'a> {
+    /// Returns the offset to UTC in seconds at the provided timestamp
+    pub fn offset_at(&mut self, timestamp: Timespec) -> Option<i32> {
+        let valid = timestamp >= self.valid_from && match self.valid_until {
+            Some(until) => timestamp < until,
+            None => true
+        };
 
-        if zones.len() == 0 {
-            let zoneinfo = Path::new("/usr/local/share/zoneinfo");
-            let _ = visitdir::visit_dirs(zoneinfo, &mut {|x| zones.push(x)});
-            used_zoneinfo = zoneinfo;
-        }
-        else
-        {
-            used_zoneinfo = zoneinfo;
+        if !valid {
+            let (from, until) = self.zone.transition_window(timestamp);
+            self.offset = self.zone.get_offset_at(timestamp);
+            self.valid_from = from;
+            self.valid_until = until;
         }
 
-        let skip = used_zoneinfo.components().count();
+        self.offset
+    }
 
-        let mut items = vec![];
+    /// Returns the offset to UTC in seconds at this moment
+    pub fn offset(&mut self) -> Option<i32> {
+        self.offset_at(time::now_utc().to_timespec())
+    }
+}
 
-        for zone in zones {
-            let path = zone.path();
-            let without_parent = path.components().skip(skip);
-            let mut rel_path = PathBuf::new();
+/// Offset to UTC of a zone together with the period during which it is in
+/// effect, see `ZoneInfo::snapshot_offset_around`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct OffsetSnapshot {
+    /// Offset to UTC in seconds
+    pub offset: i32,
+    /// Moment (inclusive) the offset came into effect, `std::i64::MIN` when
+    /// it's in effect since the beginning
+    pub start: Timespec,
+    /// Moment (exclusive) of the next transition, `None` when no transition
+    /// follows
+    pub end: Option<Timespec>,
+}
 
-            for part in without_parent {
-                rel_path.push(part.as_os_str());
-            }
+impl OffsetSnapshot {
+    /// Tells whether the offset is in effect at the provided timestamp
+    pub fn contains(&self, timestamp: Timespec) -> bool {
+        timestamp >= self.start && self.end.is_none_or(|end| timestamp < end)
+    }
 
-            if let Some(n) = rel_path.to_str() {
-                items.push(n.to_string());
-            }
+    /// Convert the provided UTC timestamp into local wall clock time using
+    /// this offset, or `None` when the offset isn't in effect at that moment
+    pub fn to_local(&self, timestamp: Timespec) -> Option<Timespec> {
+        if self.contains(timestamp) {
+            Some(Timespec::new(timestamp.sec + self.offset as i64, timestamp.nsec))
+        }
+        else {
+            None
         }
-
-        items.sort();
-
-        items
     }
+}
 
-    /// Get all transitions as a map of transition timestamps (`time::Timespec`)
-    /// and information associated to that transition (offset from UTC,
-    /// (timezone) abbreviation, and a daylight saving time indication).
-    ///
-    /// Please note that the initial timestamp is `std::i64::MIN` (when using
-    /// a 64-bit OS) and cannot be printed as timestamp.
-    pub fn get_transitions(&self) -> BTreeMap<Timespec, ZoneInfoElement> {
-        let mut map = BTreeMap::<Timespec, ZoneInfoElement>::new();
-
-        for (time, type_index) in self.zone_info
-                                      .transision_times
-                                      .iter()
-                                      .zip(self.zone_info.transision_types.iter()) {
-            let info = &self.zone_info.local_times[*type_index as usize];
-            let el = ZoneInfoElement {
-                ut_offset: info.ut_offset,
-                isdst: info.isdst,
-                abbreviation: info.abbreviation.clone(),
-                wall_clock_or_standard: self.zone_info.transition_flags1[*type_index as usize],
-                local_or_universal_time: self.zone_info.transition_flags2[*type_index as usize],
-            };
-            let _ = map.insert(time.clone(), el);
-        }
+/// Transition on which the listed transitions and the POSIX TZ rule of a zone
+/// disagree, see `ZoneInfo::posix_tail_mismatches`
+#[derive(Debug, Clone)]
+pub struct TailMismatch {
+    /// Moment of the transition
+    pub time: Timespec,
+    /// Zone info of the listed transition, `None` when the table lists no
+    /// transition at that moment
+    pub table: Option<ZoneInfoElement>,
+    /// Zone info according to the POSIX TZ rule, `None` when the rule has
+    /// no transition at that moment
+    pub posix: Option<ZoneInfoElement>,
+}
 
-        map
+/// Node of a zoneinfo directory tree, see `ZoneInfo::zone_tree`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ZoneNode {
+    /// Directory like `Europe` or `Argentina`, with its entries sorted by name
+    Directory {
+        /// Name of the directory itself
+        name: String,
+        /// Zones and directories in this directory
+        children: Vec<ZoneNode>
+    },
+    /// Zone like `Amsterdam`
+    Zone {
+        /// Name of the zone file itself
+        name: String,
+        /// Location of the zone, like `Europe/Amsterdam`, as used by `ZoneInfo::by_tz`
+        location: String
     }
+}
 
-    /// Get all leap second transitions which are coded in the zoneinfo file as
-    /// a map of timestamps and offset towards to previous time.
-    pub fn get_leap_second_transitions(&self) -> BTreeMap<Timespec, i32> {
-        let mut map = BTreeMap::<Timespec, i32>::new();
+impl ZoneNode {
+    /// inserts a zone at the provided path relative to this node
+    fn insert(&mut self, parts: &[String], location: &str) {
+        let children = match *self {
+            ZoneNode::Directory { ref mut children, .. } => children,
+            ZoneNode::Zone { .. } => return
+        };
 
-        for &(time, duration) in self.zone_info.leap_seconds_data.iter() {
-            map.insert(time.clone(), duration.clone());
+        if parts.len() == 1 {
+            children.push(ZoneNode::Zone { name: parts[0].clone(), location: location.to_string() });
+            return;
         }
 
-        map
-    }
+        let position = children.iter().position(|child| match *child {
+            ZoneNode::Directory { ref name, .. } => *name == parts[0],
+            ZoneNode::Zone { .. } => false
+        });
+        let index = match position {
+            Some(index) => index,
+            None => {
+                children.push(ZoneNode::Directory { name: parts[0].clone(), children: vec![] });
+                children.len() - 1
+            }
+        };
 
-    /// Return zone info relevant for the provided timestamp
-    ///
-    /// ```rust
-    /// extern crate time;
-    /// extern crate zoneinfo;
-    ///
-    /// use zoneinfo::ZoneInfo;
-    ///
-    /// fn main() {
-    ///     let info = ZoneInfo::get_local_zoneinfo().unwrap();
-    ///
-    ///     let actual = info.get_actual_zoneinfo(time::now_utc().to_timespec()).unwrap();
-    ///
-    ///     // A very Northern/Mid-europe based example ;-)
-    ///     println!("It's {}", if actual.isdst {"Summertime!"} else {"cold :("});
-    /// }
-    /// ```
-    pub fn get_actual_zoneinfo(&self, timestamp: Timespec) -> Option<ZoneInfoElement> {
-        let transitions = self.get_transitions();
+        children[index].insert(&parts[1..], location);
+    }
 
-        if let Some((_, zoneinfo)) = transitions.iter()
-                                                .take_while(|&(x,_)| *x < timestamp)
-                                                .last() {
-            Some(zoneinfo.clone())
-        }
-        else {
-            None
+    fn name(&self) -> &str {
+        match *self {
+            ZoneNode::Directory { ref name, .. } => name,
+            ZoneNode::Zone { ref name, .. } => name
         }
     }
 
-    /// Returns as a tuple a timestamp and related information when the next transaction will take
-    /// place.
-    ///
-    /// Note that in some regions there is no DST, and this function will return None.
-    pub fn get_next_transition_time(&self, timestamp: Timespec) -> Option<(Timespec, ZoneInfoElement)> {
-        let transitions = self.get_transitions();
-
-        if let Some((time, zoneinfo)) = transitions.iter()
-                                                .skip_while(|&(x,_)| *x < timestamp)
-                                                .next() {
-            Some((*time, zoneinfo.clone()))
-        }
-        else {
-            None
+    fn sort(&mut self) {
+        if let ZoneNode::Directory { ref mut children, .. } = *self {
+            children.sort_by(|a, b| a.name().cmp(b.name()));
+            for child in children.iter_mut() {
+                child.sort();
+            }
         }
     }
+}
 
-    /// Retrieve the daylight saving time rules for loaded zoneinfo.
-    pub fn get_dst_specifier(&self)->String {
-        self.time_zone_specifier.trim().to_string()
-    }
+/// Difference of a zone between two zoneinfo directories, see
+/// `ZoneInfo::diff_directories`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ZoneChange {
+    /// Zone only exists in the new directory
+    Added(String),
+    /// Zone only exists in the old directory
+    Removed(String),
+    /// The behavior of the zone differs (see `ZoneInfo::fingerprint`)
+    Changed(String),
+    /// The zone could not be parsed in at least one of the directories
+    Unparseable(String)
 }
 
-#[test]
-fn zoneinfo_clone() {
-    let zi = ZoneInfo::get_local_zoneinfo().unwrap();
+/// Country and coordinates of a zone as listed in `zone.tab`, see
+/// `ZoneInfo::zone_metadata`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZoneMeta {
+    /// Location of the zone, like `Europe/Amsterdam`
+    pub location: String,
+    /// ISO 3166 alpha-2 code of the country, like `NL`
+    pub country_code: String,
+    /// Latitude of the principal location in degrees, positive to the north
+    pub latitude: f64,
+    /// Longitude of the principal location in degrees, positive to the east
+    pub longitude: f64,
+    /// Comment distinguishing the zones of a country with several zones
+    pub comment: Option<String>
+}
 
-    let zic = zi.clone();
+/// Options controlling how zoneinfo files are loaded
+#[derive(Debug, Clone)]
+pub struct ParseOptions {
+    /// Refuse to read files larger than this amount of bytes. Real zoneinfo
+    /// files are only a few kilobytes; the limit protects against reading a
+    /// huge file by mistake. Defaults to 16 MiB, `None` disables the limit.
+    pub max_bytes: Option<usize>,
+    /// Years for which transitions are computed when zone info is built out
+    /// of a POSIX TZ string (see `ZoneInfo::from_posix_tz_with_options`).
+    /// Defaults to `1900..2100`, which amounts to 400 transitions for zones
+    /// with daylight saving time; outside of this range the nearest computed
+    /// transition stays in effect.
+    pub extrapolation_years: Range<i32>,
+    /// Also decode the 32-bit block of version 2+ files, which normally is
+    /// skipped, to make both leap second tables available through
+    /// `ZoneInfo::leap_second_tables`. Defaults to `false`.
+    pub parse_both_blocks: bool,
+    /// Use the 64-bit block of version 2+ files, whatever the pointer width
+    /// of the platform: `Timespec` holds `i64` seconds, so 32-bit platforms
+    /// can represent those transitions as well. Defaults to `true`; `false`
+    /// selects the 32-bit block instead, which lacks the transitions beyond
+    /// 2038 (and the ones before 1901).
+    pub prefer_64bit: bool,
+    /// Accept time zone abbreviations which aren't valid UTF-8, replacing
+    /// the invalid sequences by U+FFFD. Defaults to `false`: such files are
+    /// refused with `ZoneInfoError::BadUtf8`.
+    pub lossy_abbreviations: bool
+}
+
+impl Default for ParseOptions {
+    fn default() -> ParseOptions {
+        ParseOptions {
+            max_bytes: Some(16 * 1024 * 1024),
+            extrapolation_years: 1900..2100,
+            parse_both_blocks: false,
+            prefer_64bit: true,
+            lossy_abbreviations: false
+        }
+    }
+}
+
+/// Provider of zoneinfo files by location (like `Europe/Amsterdam`), for
+/// example a zoneinfo directory or a tzdata database embedded in the
+/// application, see `ZoneInfo::by_tz_from`
+pub trait ZoneSource {
+    /// Returns the contents of the `tzfile(5)` of the provided location
+    fn read(&self, name: &str) -> std::io::Result<Vec<u8>>;
+    /// Returns all available locations
+    fn list(&self) -> std::io::Result<Vec<String>>;
+}
+
+/// `ZoneSource` reading zoneinfo files from the file system
+#[derive(Debug, Clone)]
+pub struct FsZoneSource {
+    dirs: Vec<PathBuf>,
+    // also list the posix/ and right/ mirrors and the metadata files
+    all_files: bool
+}
+
+impl FsZoneSource {
+    /// Read zoneinfo files from the provided zoneinfo directory
+    pub fn new(dir: &Path) -> FsZoneSource {
+        FsZoneSource { dirs: vec![dir.to_path_buf()], all_files: false }
+    }
+
+    /// Read zoneinfo files from the first of the provided zoneinfo
+    /// directories containing them: a file is read from the first directory
+    /// having it and locations are listed from the first non-empty directory
+    pub fn with_dirs(dirs: &[&Path]) -> FsZoneSource {
+        FsZoneSource { dirs: dirs.iter().map(|dir| dir.to_path_buf()).collect(), all_files: false }
+    }
+
+    /// Also list the files which aren't zones of their own: the `posix/` and
+    /// `right/` mirrors of the zones and metadata files like `zone.tab` and
+    /// `leapseconds`. These are left out by default.
+    pub fn including_all_files(mut self) -> FsZoneSource {
+        self.all_files = true;
+        self
+    }
+
+    /// returns the directories of this source, in search order
+    fn dir_refs(&self) -> Vec<&Path> {
+        self.dirs.iter().map(|dir| dir.as_path()).collect()
+    }
+
+    /// returns the source for the provided value of `TZDIR`, which takes
+    /// precedence over the standard zoneinfo directories when set
+    fn for_tzdir(tzdir: Option<std::ffi::OsString>) -> FsZoneSource {
+        match tzdir {
+            Some(ref tzdir) if !tzdir.is_empty() => FsZoneSource::new(Path::new(tzdir)),
+            _ => FsZoneSource::with_dirs(&[Path::new("/usr/share/zoneinfo"), Path::new("/usr/local/share/zoneinfo")])
+        }
+    }
+}
+
+impl Default for FsZoneSource {
+    /// Read zoneinfo files from the directory in the `TZDIR` environment
+    /// variable when set. Otherwise read them from `/usr/share/zoneinfo`, or
+    /// from `/usr/local/share/zoneinfo` when the former is missing or empty
+    fn default() -> FsZoneSource {
+        FsZoneSource::for_tzdir(std::env::var_os("TZDIR"))
+    }
+}
+
+impl ZoneSource for FsZoneSource {
+    fn read(&self, name: &str) -> std::io::Result<Vec<u8>> {
+        // this could have be very simple whether path.is_file() would have be
+        // stable.
+        let path = self.dirs
+                       .iter()
+                       .map(|dir| dir.join(name))
+                       .find(|path| metadata(path).map(|m| m.is_file()).unwrap_or(false))
+                       .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound,
+                           "provided location not found"))?;
+        let mut buffer = Vec::<u8>::new();
+
+        File::open(path)?.read_to_end(&mut buffer)?;
+
+        Ok(buffer)
+    }
+
+    fn list(&self) -> std::io::Result<Vec<String>> {
+        let items = self.dirs
+                        .iter()
+                        .map(|dir| locations_in(dir))
+                        .find(|items| !items.is_empty())
+                        .unwrap_or_default();
+
+        Ok(items.into_iter().filter(|item| self.all_files || is_zone_location(item)).collect())
+    }
+}
+
+/// Time zone information
+#[derive(Clone)]
+pub struct ZoneInfo {
+    zone_info:ZoneInfoInner,
+    time_zone_specifier:String,
+    // the POSIX TZ string parsed once, when valid
+    posix_tz: Option<PosixTz>,
+    // the transitions as returned by `get_transitions`, built on first use
+    transition_cache: OnceLock<BTreeMap<Timespec, ZoneInfoElement>>,
+    // leap seconds of the 32-bit and 64-bit block, when both were decoded
+    leap_second_tables: Option<(LeapSeconds, LeapSeconds)>,
+    // location like "Europe/Amsterdam", when loaded by location
+    name: Option<String>
+}
+
+impl ZoneInfo {
+    /// Load zone info from a provided `tzfile(5)`. These files are often
+    /// located in `/usr/share/zoneinfo` or `/usr/local/share/info`. Depending on
+    /// your system the systems zoneinfo file is located in `/etc/localtime`.
+    pub fn new(zoneinfofile: &Path) -> Result<ZoneInfo, ZoneInfoError> {
+        ZoneInfo::new_with_options(zoneinfofile, &ParseOptions::default())
+    }
+
+    /// Load zone info from a provided `tzfile(5)` using the provided options.
+    pub fn new_with_options(zoneinfofile: &Path, options: &ParseOptions) -> Result<ZoneInfo, ZoneInfoError> {
+        let mut file = File::open(zoneinfofile)?;
+        let mut buffer = Vec::<u8>::new();
+
+        match options.max_bytes {
+            Some(max_bytes) => {
+                // read one byte more than allowed to detect oversized files
+                file.by_ref().take(max_bytes as u64 + 1).read_to_end(&mut buffer)?;
+                if buffer.len() > max_bytes {
+                    return Err(ZoneInfoError::FileTooLarge);
+                }
+            },
+            None => {
+                file.read_to_end(&mut buffer)?;
+            }
+        }
+
+        ZoneInfo::from_bytes_with_options(&buffer[..], options)
+    }
+
+    /// Parse zone info from the contents of a `tzfile(5)` which is already
+    /// in memory, for example embedded in the application.
+    pub fn from_bytes(data: &[u8]) -> Result<ZoneInfo, ZoneInfoError> {
+        ZoneInfo::from_bytes_with_options(data, &ParseOptions::default())
+    }
+
+    /// Parse zone info from the contents of a `tzfile(5)` using the provided
+    /// options. `ParseOptions::max_bytes` only applies to reading files.
+    ///
+    /// The version byte of the first header is inspected before any table is
+    /// decoded. For version 2 files and later the 32-bit block is only a
+    /// compatibility copy (and is empty for files compiled with `zic -b slim`),
+    /// so when the 64-bit data is going to be used that block is skipped
+    /// instead of decoded (unless `ParseOptions::parse_both_blocks` is set).
+    pub fn from_bytes_with_options(data: &[u8], options: &ParseOptions) -> Result<ZoneInfo, ZoneInfoError> {
+        let mut cursor = Cursor::new(data);
+        let mut tail = String::new();
+        let mut leap_second_tables = None;
+
+        let v1 = TzHead::new(&mut cursor, 4, consume_32bit_timestamps)?;
+        let tz = if v1.inner.tzh_version >= '2' {
+            let use_b64 = options.prefer_64bit;
+            let b32 = if use_b64 && !options.parse_both_blocks {
+                let position = cursor.position();
+                cursor.set_position(position.saturating_add(v1.inner.data_block_len()?));
+                None
+            }
+            else
+            {
+                Some(read_zone_data(&mut cursor, v1, options)?)
+            };
+            let b64 = read_zone_info(&mut cursor, 8, consume_64bit_timestamps, options)?;
+            tail = read_tail(&mut cursor)?;
+
+            if let (true, Some(ref b32)) = (options.parse_both_blocks, &b32) {
+                leap_second_tables = Some((b32.leap_seconds_data.clone(), b64.leap_seconds_data.clone()));
+            }
+
+            match b32 {
+                Some(b32) if !use_b64 => b32,
+                _ => b64
+            }
+        }
+        else {
+            read_zone_data(&mut cursor, v1, options)?
+        };
+
+        let posix_tz = PosixTz::parse(tail.trim()).ok();
+
+        Ok(ZoneInfo{zone_info:tz, time_zone_specifier:tail, posix_tz, transition_cache: OnceLock::new(),
+                    leap_second_tables, name: None})
+    }
+
+    /// Read only the header of the contents of a `tzfile(5)`, without
+    /// decoding any table, for example to quickly scan many files for leap
+    /// seconds. For version 2 files and later the header of the 64-bit block
+    /// is returned, which describes the data that is normally used.
+    pub fn parse_header_only(data: &[u8]) -> Result<ZoneHeader, ZoneInfoError> {
+        let mut cursor = Cursor::new(data);
+        let v1 = TzHead::new(&mut cursor, 4, consume_32bit_timestamps)?;
+
+        if v1.inner.tzh_version < '2' {
+            return Ok(v1.inner.zone_header());
+        }
+
+        let position = cursor.position();
+        cursor.set_position(position.saturating_add(v1.inner.data_block_len()?));
+        let v2 = TzHead::new(&mut cursor, 8, consume_64bit_timestamps)?;
+
+        Ok(v2.inner.zone_header())
+    }
+
+    /// Tells whether the contents of a `tzfile(5)` announce a 64-bit block
+    /// (version 2 files and later), looking at the first header only. Legacy
+    /// version 1 files only carry 32-bit data without a POSIX TZ string.
+    pub fn has_64bit_block(data: &[u8]) -> Result<bool, ZoneInfoError> {
+        let v1 = TzHead::new(&mut Cursor::new(data), 4, consume_32bit_timestamps)?;
+
+        Ok(v1.inner.tzh_version >= '2')
+    }
+
+    /// Build zone info out of a POSIX TZ string (like
+    /// `CET-1CEST,M3.5.0,M10.5.0/3`) instead of a zoneinfo file. The
+    /// transitions are computed for the years `1900..2100`.
+    pub fn from_posix_tz(spec: &str) -> Result<ZoneInfo, ZoneInfoError> {
+        ZoneInfo::from_posix_tz_with_options(spec, &ParseOptions::default())
+    }
+
+    /// Build zone info out of a POSIX TZ string, computing transitions for
+    /// the years configured in `ParseOptions::extrapolation_years`.
+    pub fn from_posix_tz_with_options(spec: &str, options: &ParseOptions) -> Result<ZoneInfo, ZoneInfoError> {
+        let tz = PosixTz::parse(spec)?;
+        let transitions = tz.transitions(options.extrapolation_years.clone());
+
+        let mut local_times = vec![TzType {
+            ut_offset: tz.std_ut_offset,
+            isdst: false,
+            abbreviation: tz.std_abbreviation.clone(),
+        }];
+        if let Some(ref dst) = tz.dst {
+            local_times.push(TzType {
+                ut_offset: dst.ut_offset,
+                isdst: true,
+                abbreviation: dst.abbreviation.clone(),
+            });
+        }
+
+        // the type in effect before the first transition is the opposite of
+        // the one the first transition switches to
+        let initial = match transitions.first() {
+            Some(&(_, isdst)) => !isdst as u8,
+            None => {
+                let start = civil::days_from_civil(options.extrapolation_years.start as i64, 1, 1);
+                tz.isdst_at(start * 86400) as u8
+            }
+        };
+        let mut transition_times = vec![Timespec::new(i64::MIN, 0)];
+        let mut transition_types = vec![initial];
+        for &(time, isdst) in &transitions {
+            transition_times.push(Timespec::new(time, 0));
+            transition_types.push(isdst as u8);
+        }
+
+        let mut abbreviation_chars = vec![];
+        for local_time in &local_times {
+            abbreviation_chars.extend(local_time.abbreviation.bytes());
+            abbreviation_chars.push(0);
+        }
+
+        let header = TzHeadInner {
+            tzh_magic: *b"TZif",
+            tzh_version: '2',
+            tzh_ttigmtcnt: 0,
+            tzh_ttisstdcnt: 0,
+            tzh_leapcnt: 0,
+            tzh_timecnt: transition_times.len() as u32,
+            tzh_typecnt: local_times.len() as u32,
+            tzh_charcnt: abbreviation_chars.len() as u32,
+            time_size: 8,
+        };
+
+        Ok(ZoneInfo {
+            zone_info: ZoneInfoInner {
+                header,
+                transision_times: transition_times,
+                transision_types: transition_types,
+                local_times,
+                abbreviation_chars,
+                leap_seconds_data: vec![],
+                transition_flags1: vec![],
+                transition_flags2: vec![],
+            },
+            time_zone_specifier: spec.to_string(),
+            posix_tz: Some(tz),
+            transition_cache: OnceLock::new(),
+            leap_second_tables: None,
+            name: None
+        })
+    }
+
+    /// Build zone info purely out of a POSIX TZ string, with transitions
+    /// computed for the provided (UTC) years only, for example to check the
+    /// rules of a TZ string in isolation. Malformed TZ strings and empty year
+    /// ranges are rejected.
+    pub fn from_posix_tz_only(spec: &str, year_range: Range<i32>) -> Result<ZoneInfo, ZoneInfoError> {
+        if year_range.start >= year_range.end {
+            return Err(ZoneInfoError::EmptyYearRange(year_range));
+        }
+
+        let options = ParseOptions { extrapolation_years: year_range, ..ParseOptions::default() };
+
+        ZoneInfo::from_posix_tz_with_options(spec, &options)
+    }
+
+    /// Load zone info based on a provided location.
+    ///
+    /// ```rust
+    /// use zoneinfo::ZoneInfo;
+    /// let info = ZoneInfo::by_tz("Europe/Amsterdam").unwrap();
+    ///
+    /// println!("Daylight saving time rules are: {}", info.get_dst_specifier());
+    /// ```
+    ///
+    /// Not available for Windows users
+    pub fn by_tz(location: &str) -> Result<ZoneInfo, ZoneInfoError> {
+        ZoneInfo::by_tz_in(location, &FsZoneSource::default().dir_refs())
+    }
+
+    /// Load the leap second aware variant of the zone info of the provided
+    /// location from the `right/` subdirectory of the zoneinfo directories
+    /// (see `by_tz`), for precise TAI/UTC work.
+    ///
+    /// The regular ("posix") zones ignore leap seconds: their timestamps count
+    /// 86400 seconds a day like `Timespec` does. The timestamps of the
+    /// `right/` zones count the inserted leap seconds as well, and the leap
+    /// seconds are listed (see `get_leap_second_transitions`). Only use them
+    /// with timestamps from a clock that counts leap seconds. As POSIX TZ
+    /// strings can't express leap seconds these zones come without one, so
+    /// beyond the last listed transition nothing is extrapolated.
+    pub fn by_tz_right(location: &str) -> Result<ZoneInfo, ZoneInfoError> {
+        let source = FsZoneSource::default();
+        let dirs: Vec<PathBuf> = source.dir_refs().iter().map(|dir| dir.join("right")).collect();
+        let dirs: Vec<&Path> = dirs.iter().map(|dir| dir.as_path()).collect();
+
+        ZoneInfo::by_tz_in(location, &dirs)
+    }
+
+    /// Retrieve the country and coordinates of all zones listed in the
+    /// `zone.tab` file of the zoneinfo directory (see `by_tz`), in the order
+    /// of that file. Returns an empty list when there's no such file.
+    pub fn all_zone_metadata() -> Vec<ZoneMeta> {
+        FsZoneSource::default()
+            .dir_refs()
+            .iter()
+            .filter_map(|dir| std::fs::read_to_string(dir.join("zone.tab")).ok())
+            .map(|contents| parse_zone_tab(&contents))
+            .next()
+            .unwrap_or_default()
+    }
+
+    /// Retrieve the country and coordinates of the provided location as
+    /// listed in `zone.tab`, see `all_zone_metadata`. Returns `None` for
+    /// locations which aren't listed, like aliases (`Japan`) and zones not
+    /// bound to a country (`Etc/UTC`).
+    pub fn zone_metadata(location: &str) -> Option<ZoneMeta> {
+        ZoneInfo::all_zone_metadata().into_iter().find(|meta| meta.location == location)
+    }
+
+    /// Returns the canonical name of the provided location by following the
+    /// symbolic links in the zoneinfo directory (see `by_tz`), like
+    /// `America/New_York` for the legacy alias `US/Eastern`. Absolute paths
+    /// are resolved as well, so `/etc/localtime` yields the name of the zone
+    /// configured for this machine.
+    ///
+    /// Returns `None` when the location doesn't exist or doesn't resolve to a
+    /// file in the zoneinfo directory. Aliases installed as hard links or
+    /// copies are reported as is.
+    pub fn canonical_name(location: &str) -> Option<String> {
+        canonical_name_in(location, &FsZoneSource::default().dir_refs())
+    }
+
+    /// Load zone info based on a provided location, searching the provided
+    /// zoneinfo directories in order (see `FsZoneSource::with_dirs`) instead
+    /// of the system zoneinfo directories, for example on systems with
+    /// zoneinfo files in `/opt/tz`.
+    pub fn by_tz_in(location: &str, dirs: &[&Path]) -> Result<ZoneInfo, ZoneInfoError> {
+        ZoneInfo::by_tz_from(&FsZoneSource::with_dirs(dirs), location)
+    }
+
+    /// Load zone info based on a provided location, reading the zoneinfo file
+    /// from the provided source instead of the system zoneinfo directory.
+    /// Files exceeding the default `ParseOptions::max_bytes` are refused.
+    pub fn by_tz_from<S: ZoneSource + ?Sized>(source: &S, location: &str) -> Result<ZoneInfo, ZoneInfoError> {
+        let all = source.list()?;
+        if !all.iter().any(|item| item == location) {
+            return Err(ZoneInfoError::Io(std::io::Error::new(std::io::ErrorKind::NotFound,
+                "provided location not found")));
+        }
+
+        ZoneInfo::read_from(source, location)
+    }
+
+    /// returns the zone info of the provided location read from the provided
+    /// source, which is expected to list that location
+    fn read_from<S: ZoneSource + ?Sized>(source: &S, location: &str) -> Result<ZoneInfo, ZoneInfoError> {
+        let options = ParseOptions::default();
+        let buffer = source.read(location)?;
+        if options.max_bytes.is_some_and(|max_bytes| buffer.len() > max_bytes) {
+            return Err(ZoneInfoError::FileTooLarge);
+        }
+
+        let mut zone = ZoneInfo::from_bytes_with_options(&buffer[..], &options)?;
+        zone.name = Some(location.to_string());
+
+        Ok(zone)
+    }
+
+    /// Retrieve local zoneinfo settings
+    ///
+    /// Like POSIX systems do, the `TZ` environment variable is honored first:
+    /// a location like `Europe/Berlin` is loaded using `by_tz`, a path (like
+    /// `:/path/to/file`, the colon is optional) is loaded directly and other
+    /// values are interpreted as POSIX TZ string (like `CET-1CEST`). Only
+    /// when `TZ` is unset or empty `/etc/localtime` is used.
+    ///
+    /// Not available for Windows users
+    pub fn get_local_zoneinfo() -> Result<ZoneInfo, ZoneInfoError> {
+        ZoneInfo::for_tz_variable(std::env::var("TZ").ok())
+    }
+
+    /// returns the local zone info for the provided value of `TZ`, see
+    /// `get_local_zoneinfo`
+    fn for_tz_variable(tz: Option<String>) -> Result<ZoneInfo, ZoneInfoError> {
+        let tz = match tz {
+            Some(tz) if !tz.is_empty() => tz,
+            _ => return ZoneInfo::new(Path::new("/etc/localtime"))
+        };
+        let spec = tz.strip_prefix(':').unwrap_or(&tz);
+
+        if spec.starts_with('/') {
+            ZoneInfo::new(Path::new(spec))
+        }
+        else {
+            ZoneInfo::by_tz(spec).or_else(|err| ZoneInfo::from_posix_tz(spec).map_err(|_| err))
+        }
+    }
+
+    /// Retrieve all supported zoneinfo locations available at this machine.
+    /// These locations can be used by `ZoneInfo::by_tz`. The `posix/` and
+    /// `right/` mirrors of the zones and metadata files like `zone.tab` are
+    /// left out, see `FsZoneSource::including_all_files`.
+    ///
+    /// Not available for Windows users
+    pub fn get_tz_locations() -> Vec<String> {
+        ZoneInfo::get_tz_locations_in(&FsZoneSource::default().dir_refs())
+    }
+
+    /// returns the zone info of all locations available at this machine (see
+    /// `get_tz_locations`), listing the zoneinfo directory only once.
+    /// Locations which can't be parsed are skipped.
+    fn system_zones() -> impl Iterator<Item = (String, ZoneInfo)> {
+        let source = FsZoneSource::default();
+
+        ZoneInfo::get_tz_locations_from(&source)
+            .into_iter()
+            .filter_map(move |location| {
+                let zone = ZoneInfo::read_from(&source, &location).ok()?;
+                Some((location, zone))
+            })
+    }
+
+    /// Retrieve all (sorted) locations of the first non-empty one of the
+    /// provided zoneinfo directories, see `by_tz_in`.
+    pub fn get_tz_locations_in(dirs: &[&Path]) -> Vec<String> {
+        ZoneInfo::get_tz_locations_from(&FsZoneSource::with_dirs(dirs))
+    }
+
+    /// Retrieve all locations available from the provided source (sorted),
+    /// or an empty list when the source fails to list them.
+    pub fn get_tz_locations_from<S: ZoneSource + ?Sized>(source: &S) -> Vec<String> {
+        let mut items = source.list().unwrap_or_default();
+        items.sort();
+
+        items
+    }
+
+    /// Retrieve the zoneinfo locations in the provided directory as a tree of
+    /// directories (like `America` and `America/Indiana`) and zones (like
+    /// `America/Indiana/Indianapolis`), for example to present a collapsible
+    /// region picker.
+    pub fn zone_tree(dir: &Path) -> ZoneNode {
+        let mut zones = vec![];
+        let _ = visitdir::visit_dirs(dir, &mut {|x| zones.push(x)});
+
+        let name = dir.file_name().and_then(|n| n.to_str()).unwrap_or("").to_string();
+        let mut root = ZoneNode::Directory { name, children: vec![] };
+        let skip = dir.components().count();
+
+        for zone in zones {
+            let path = zone.path();
+            let parts: Option<Vec<String>> = path.components()
+                                                 .skip(skip)
+                                                 .map(|part| part.as_os_str().to_str().map(|p| p.to_string()))
+                                                 .collect();
+
+            if let Some(parts) = parts {
+                root.insert(&parts, &parts.join("/"));
+            }
+        }
+
+        root.sort();
+
+        root
+    }
+
+    /// Compare all zones of two zoneinfo directories, for example to list the
+    /// zones changed by a tzdata release. Zones which behave the same (see
+    /// `fingerprint`) are not reported.
+    pub fn diff_directories(old: &Path, new: &Path) -> Vec<ZoneChange> {
+        let old_locations = locations_in(old);
+        let new_locations = locations_in(new);
+        let mut all: Vec<&String> = old_locations.iter().chain(new_locations.iter()).collect();
+        all.sort();
+        all.dedup();
+
+        let mut changes = vec![];
+
+        for location in all {
+            if !new_locations.contains(location) {
+                changes.push(ZoneChange::Removed(location.clone()));
+            }
+            else if !old_locations.contains(location) {
+                changes.push(ZoneChange::Added(location.clone()));
+            }
+            else {
+                match (ZoneInfo::new(&old.join(location)), ZoneInfo::new(&new.join(location))) {
+                    (Ok(before), Ok(after)) => {
+                        if before.fingerprint() != after.fingerprint() {
+                            changes.push(ZoneChange::Changed(location.clone()));
+                        }
+                    },
+                    _ => changes.push(ZoneChange::Unparseable(location.clone()))
+                }
+            }
+        }
+
+        changes
+    }
+
+    /// Group all zoneinfo locations available at this machine (see
+    /// `get_tz_locations`) by their offset to UTC in seconds at the provided
+    /// moment, for example to show how the zones of the world cluster.
+    ///
+    /// Locations which can't be parsed or have no zone information for that
+    /// moment are skipped.
+    pub fn offset_distribution(at: Timespec) -> BTreeMap<i32, Vec<String>> {
+        let mut distribution = BTreeMap::<i32, Vec<String>>::new();
+
+        for (location, zone) in ZoneInfo::system_zones() {
+            if let Some(info) = zone.element_at_extrapolated(at) {
+                distribution.entry(info.ut_offset).or_default().push(location);
+            }
+        }
+
+        distribution
+    }
+
+    /// Returns the tzdata release (like `2024a`) of the provided zoneinfo
+    /// directory, as found in its `+VERSION` file or in the `# version`
+    /// comment on the first line of its `tzdata.zi` file.
+    pub fn tzdata_version(dir: &Path) -> Option<String> {
+        use std::io::BufRead;
+
+        if let Ok(contents) = std::fs::read_to_string(dir.join("+VERSION")) {
+            let version = contents.trim();
+            if !version.is_empty() {
+                return Some(version.to_string());
+            }
+        }
+
+        let mut line = String::new();
+        let file = File::open(dir.join("tzdata.zi")).ok()?;
+        std::io::BufReader::new(file).read_line(&mut line).ok()?;
+
+        let version = line.trim().strip_prefix("# version")?.trim();
+        if version.is_empty() {
+            None
+        }
+        else {
+            Some(version.to_string())
+        }
+    }
+
+    /// Group the zoneinfo locations available at this machine by their
+    /// offset to UTC at the provided moment (see `offset_distribution`) for
+    /// a classic time zone picker: labels like `(UTC+01:00)` map to the
+    /// (sorted) display names of the zones, which is the last part of the
+    /// location with spaces instead of underscores (like `New York`).
+    ///
+    /// Locations which can't be parsed are skipped.
+    pub fn group_zones_by_offset_label(at: Timespec) -> BTreeMap<String, Vec<String>> {
+        let mut groups = BTreeMap::<String, Vec<String>>::new();
+
+        for (offset, locations) in ZoneInfo::offset_distribution(at) {
+            let mut names: Vec<String> = locations.iter()
+                                                  .map(|location| location.rsplit('/').next().unwrap_or(location))
+                                                  .map(|name| name.replace('_', " "))
+                                                  .collect();
+            names.sort();
+            names.dedup();
+
+            groups.entry(format!("(UTC{})", offset_string(offset, ":"))).or_default().extend(names);
+        }
+
+        groups
+    }
+
+    /// Retrieve the zoneinfo locations available at this machine (see
+    /// `get_tz_locations`) which have a transition within `tolerance` of the
+    /// provided moment, for example to find the zones which changed their
+    /// clocks around a rule change. Beyond the last listed transition of a
+    /// zone the transitions of its POSIX TZ rule are taken into account (see
+    /// `get_next_transition_time`).
+    ///
+    /// Locations which can't be parsed are skipped.
+    pub fn zones_with_transition_near(at: Timespec, tolerance: Duration) -> Vec<String> {
+        let start = at - tolerance;
+        let end = at + tolerance;
+
+        ZoneInfo::system_zones()
+            .filter(|(_, zone)| zone.get_next_transition_time(start - Duration::nanoseconds(1))
+                                    .is_some_and(|(time, _)| time < end))
+            .map(|(location, _)| location)
+            .collect()
+    }
+
+    /// Returns the distinct offsets to UTC in seconds (sorted) of all
+    /// zoneinfo locations available at this machine at the provided moment,
+    /// see `offset_distribution`.
+    pub fn active_offsets_at(at: Timespec) -> Vec<i32> {
+        ZoneInfo::offset_distribution(at).keys().cloned().collect()
+    }
+
+    /// Retrieve the zoneinfo locations available at this machine (see
+    /// `get_tz_locations`) which still observe daylight saving time: zones
+    /// which have a transition to daylight saving time coming up, either
+    /// listed or computed from the POSIX TZ rule. Zones which abolished
+    /// daylight saving time are left out.
+    ///
+    /// Locations which can't be parsed are skipped.
+    pub fn dst_observing_zones() -> Vec<String> {
+        let now = time::get_time();
+
+        ZoneInfo::system_zones()
+            .filter(|(_, zone)| zone.observes_dst() &&
+                                zone.upcoming_transitions(now, 2).iter().any(|(_, info)| info.isdst))
+            .map(|(location, _)| location)
+            .collect()
+    }
+
+    /// Retrieve the zoneinfo locations available at this machine (see
+    /// `get_tz_locations`) which behave exactly like the provided location
+    /// (see `fingerprint`), like its aliases (`Japan` for `Asia/Tokyo`). The
+    /// provided location itself is left out.
+    ///
+    /// Returns an empty list when the provided location can't be loaded;
+    /// other locations which can't be parsed are skipped.
+    pub fn equivalent_zones(name: &str) -> Vec<String> {
+        let fingerprint = match ZoneInfo::by_tz(name) {
+            Ok(zone) => zone.fingerprint(),
+            Err(_) => return vec![]
+        };
+
+        ZoneInfo::system_zones()
+            .filter(|(location, zone)| location != name && zone.fingerprint() == fingerprint)
+            .map(|(location, _)| location)
+            .collect()
+    }
+
+    /// Get all transitions as a map of transition timestamps (`time::Timespec`)
+    /// and information associated to that transition (offset from UTC,
+    /// (timezone) abbreviation, and a daylight saving time indication).
+    ///
+    /// Please note that the initial timestamp is `std::i64::MIN` (when using
+    /// a 64-bit OS) and cannot be printed as timestamp.
+    ///
+    /// Local time types without standard/wall clock or UT/local indicator in
+    /// the file report `TransitionTimeFlag::WallClock` and
+    /// `TransitionTimeFlag::Local`.
+    pub fn get_transitions(&self) -> BTreeMap<Timespec, ZoneInfoElement> {
+        self.transition_map().clone()
+    }
+
+    /// Iterate over the transitions in order of time, yielding the same
+    /// timestamps (including the initial one) and zone info as
+    /// `get_transitions` without building a map. The zone info is built
+    /// lazily for each transition.
+    pub fn transitions(&self) -> impl Iterator<Item = (Timespec, ZoneInfoElement)> + '_ {
+        self.zone_info
+            .transision_times
+            .iter()
+            .zip(self.zone_info.transision_types.iter())
+            .map(move |(&time, &type_index)| (time, self.element(type_index as usize)))
+    }
+
+    /// returns the transitions like `get_transitions` does, built once and
+    /// borrowed afterwards
+    fn transition_map(&self) -> &BTreeMap<Timespec, ZoneInfoElement> {
+        self.transition_cache.get_or_init(|| {
+            let mut map = BTreeMap::<Timespec, ZoneInfoElement>::new();
+
+            for (time, type_index) in self.zone_info
+                                          .transision_times
+                                          .iter()
+                                          .zip(self.zone_info.transision_types.iter()) {
+                let _ = map.insert(*time, self.element(*type_index as usize));
+            }
+
+            map
+        })
+    }
+
+    /// Get all transitions as `(UTC timestamp, local timestamp, zone info)`,
+    /// where the local timestamp is the wall clock time right after the
+    /// transition (the UTC timestamp plus the new offset).
+    ///
+    /// The initial `std::i64::MIN` timestamp is not part of the result; local
+    /// timestamps saturate instead of overflowing at the extremes.
+    pub fn transition_utc_and_local(&self) -> Vec<(Timespec, Timespec, ZoneInfoElement)> {
+        self.transition_map()
+            .iter()
+            .filter(|&(time, _)| time.sec != i64::MIN)
+            .map(|(&time, info)| {
+                let local = Timespec::new(time.sec.saturating_add(info.ut_offset as i64), time.nsec);
+                (time, local, info.clone())
+            })
+            .collect()
+    }
+
+    /// Iterate lazily over the transitions (ordered by time) whose resulting
+    /// zone info satisfies the provided predicate, for example
+    /// `zone.transitions_where(|info| info.isdst)`.
+    pub fn transitions_where<'a, F>(&'a self, pred: F) -> impl Iterator<Item=(Timespec, ZoneInfoElement)> + 'a
+        where F: Fn(&ZoneInfoElement) -> bool + 'a {
+        self.zone_info
+            .transision_times
+            .iter()
+            .zip(self.zone_info.transision_types.iter())
+            .map(move |(time, type_index)| (*time, self.element(*type_index as usize)))
+            .filter(move |(_, info)| pred(info))
+    }
+
+    /// Get all transitions as a flat table of `(epoch seconds, offset from
+    /// UTC in seconds, daylight saving time, abbreviation)` tuples, for
+    /// example to export them as CSV.
+    ///
+    /// Unlike `get_transitions` the initial `std::i64::MIN` timestamp is not
+    /// part of the table.
+    pub fn transition_table(&self) -> Vec<(i64, i32, bool, String)> {
+        self.transition_map()
+            .iter()
+            .filter(|&(time, _)| time.sec != i64::MIN)
+            .map(|(time, info)| (time.sec, info.ut_offset, info.isdst, info.abbreviation.clone()))
+            .collect()
+    }
+
+    /// Returns the moment the zone first used the provided offset to UTC (in
+    /// seconds), like when `Europe/Amsterdam` first used UTC+1 with
+    /// `first_use_of_offset(3600)`, or `None` when it never did.
+    ///
+    /// Like for `get_transitions` an offset in effect since the beginning is
+    /// reported at `std::i64::MIN`.
+    pub fn first_use_of_offset(&self, offset_seconds: i32) -> Option<Timespec> {
+        self.zone_info
+            .transision_times
+            .iter()
+            .zip(self.zone_info.transision_types.iter())
+            .find(|&(_, &index)| self.zone_info.local_times[index as usize].ut_offset == offset_seconds)
+            .map(|(time, _)| *time)
+    }
+
+    /// Get the offsets to UTC in seconds as a step timeline of `(transition
+    /// time, new offset)` pairs, ordered by time, for example to chart the
+    /// offset of a zone over time.
+    ///
+    /// The initial `std::i64::MIN` timestamp is not part of the timeline; the
+    /// offset in effect before the first transition is available through
+    /// `pretransition_type`.
+    pub fn offset_timeline(&self) -> Vec<(Timespec, i32)> {
+        self.transition_table()
+            .into_iter()
+            .map(|(time, ut_offset, _, _)| (Timespec::new(time, 0), ut_offset))
+            .collect()
+    }
+
+    /// Get all leap second transitions which are coded in the zoneinfo file as
+    /// a map of timestamps and offset towards to previous time.
+    pub fn get_leap_second_transitions(&self) -> BTreeMap<Timespec, i32> {
+        let mut map = BTreeMap::<Timespec, i32>::new();
+
+        for &(time, duration) in self.zone_info.leap_seconds_data.iter() {
+            map.insert(time, duration);
+        }
+
+        map
+    }
+
+    /// returns the index of the local time type in effect at the provided
+    /// timestamp; a transition is in effect from its own timestamp onwards
+    ///
+    /// the nanoseconds of the timestamp take part in the comparison, so a
+    /// moment just after a transition resolves to that transition
+    fn type_index_at(&self, timestamp: Timespec) -> Option<usize> {
+        let count = self.transitions_up_to(timestamp);
+
+        if count == 0 {
+            None
+        }
+        else {
+            Some(self.zone_info.transision_types[count - 1] as usize)
+        }
+    }
+
+    /// returns the number of transitions taking place at or before the
+    /// provided timestamp, found by binary search as the transition times are
+    /// ascending
+    fn transitions_up_to(&self, timestamp: Timespec) -> usize {
+        self.zone_info.transision_times.partition_point(|&time| time <= timestamp)
+    }
+
+    /// returns the zone info of the local time type with the provided index
+    ///
+    /// the standard/wall clock and UT/local indicator tables may be shorter
+    /// than the number of local time types (usually they're empty), missing
+    /// indicators default to wall clock and local time as tzfile(5) says
+    fn element(&self, type_index: usize) -> ZoneInfoElement {
+        let info = &self.zone_info.local_times[type_index];
+
+        ZoneInfoElement {
+            ut_offset: info.ut_offset,
+            isdst: info.isdst,
+            abbreviation: info.abbreviation.clone(),
+            wall_clock_or_standard: self.zone_info.transition_flags1.get(type_index)
+                                        .cloned().unwrap_or(TransitionTimeFlag::WallClock),
+            local_or_universal_time: self.zone_info.transition_flags2.get(type_index)
+                                        .cloned().unwrap_or(TransitionTimeFlag::Local),
+        }
+    }
+
+    /// returns the moment the zone info at the provided timestamp came into
+    /// effect and the moment of the next transition (if any)
+    fn transition_window(&self, timestamp: Timespec) -> (Timespec, Option<Timespec>) {
+        let times = &self.zone_info.transision_times;
+        let count = self.transitions_up_to(timestamp);
+        let from = if count == 0 { Timespec::new(i64::MIN, 0) } else { times[count - 1] };
+
+        if count < times.len() || self.current_date_source(timestamp) == DateSource::Table {
+            return (from, times.get(count).cloned());
+        }
+
+        let transitions = self.extrapolated_transitions(timestamp);
+        let from = transitions.iter()
+                              .rev()
+                              .map(|&(time, _)| time)
+                              .find(|&time| time <= timestamp)
+                              .unwrap_or(from);
+        let until = transitions.iter()
+                               .map(|&(time, _)| time)
+                               .find(|&time| time > timestamp);
+
+        (from, until)
+    }
+
+    /// returns the transitions of the POSIX TZ rule after the last listed
+    /// transition, in the years surrounding the provided timestamp
+    fn extrapolated_transitions(&self, timestamp: Timespec) -> Vec<(Timespec, ZoneInfoElement)> {
+        let tz = match self.posix_tz() {
+            Some(tz) => tz,
+            None => return vec![]
+        };
+        let after = self.tabulated_range().map_or(i64::MIN, |(_, last)| last.sec);
+        let year = posix_year(timestamp.sec);
+
+        posix::transitions_in_years(tz, year - 1..year + 2)
+          .into_iter()
+          .filter(|&(time, _)| time > after)
+          .map(|(time, isdst)| (Timespec::new(time, 0), posix_element(tz, isdst)))
+          .collect()
+    }
+
+    /// returns the transitions from `start` (inclusive) until `end`
+    /// (exclusive) like `get_transitions_in_range`, followed by the ones of
+    /// the POSIX TZ rule after the last listed transition
+    ///
+    /// at most `MAX_EXTRAPOLATED_YEARS` years of the rule are computed,
+    /// starting at the later of `start` and the last listed transition
+    fn extrapolated_transitions_between(&self, start: Timespec, end: Timespec)
+                                        -> Vec<(Timespec, ZoneInfoElement)> {
+        let mut transitions = self.get_transitions_in_range(start, end);
+        let tz = match self.posix_tz() {
+            Some(tz) if start < end => tz,
+            _ => return transitions
+        };
+        let after = self.tabulated_range().map_or(i64::MIN, |(_, last)| last.sec);
+        let first_year = posix_year(std::cmp::max(start.sec, after));
+        let end_year = std::cmp::min(posix_year(end.sec) + 1, first_year + MAX_EXTRAPOLATED_YEARS);
+
+        transitions.extend(
+            posix::transitions_in_years(tz, first_year..end_year)
+              .into_iter()
+              .map(|(time, isdst)| (Timespec::new(time, 0), isdst))
+              .filter(|&(time, _)| time.sec > after && time >= start && time < end)
+              .map(|(time, isdst)| (time, posix_element(tz, isdst))));
+        transitions
+    }
+
+    /// Returns a handle for cheap repeated lookups of the offset to UTC, for
+    /// example to stamp log lines. The handle remembers the offset together
+    /// with the moments of the surrounding transitions and only consults the
+    /// zone info again once a timestamp outside of that period is queried.
+    pub fn cached_offset(&self) -> CachedOffset<'_> {
+        // the empty period makes the first query look up the offset
+        CachedOffset {
+            zone: self,
+            offset: None,
+            valid_from: Timespec::new(i64::MAX, 0),
+            valid_until: None
+        }
+    }
+
+    /// Returns the offset to UTC in effect at the provided timestamp together
+    /// with the period during which it applies, for example to convert many
+    /// timestamps close to each other without looking up each of them.
+    ///
+    /// Before the first transition the offset of `pretransition_type` is
+    /// used.
+    pub fn snapshot_offset_around(&self, timestamp: Timespec) -> OffsetSnapshot {
+        let (start, end) = self.transition_window(timestamp);
+        let offset = match self.get_actual_zoneinfo(timestamp) {
+            Some(info) => info.ut_offset,
+            None => self.pretransition_type().map_or(0, |info| info.ut_offset)
+        };
+
+        OffsetSnapshot { offset, start, end }
+    }
+
+    /// Get the leap second tables of both the 32-bit and the 64-bit block of
+    /// a version 2+ file, in that order. The 32-bit block only covers the
+    /// range until 2038, so those tables might differ.
+    ///
+    /// Only available when the zoneinfo was loaded with
+    /// `ParseOptions::parse_both_blocks`.
+    pub fn leap_second_tables(&self) -> Option<(BTreeMap<Timespec, i32>, BTreeMap<Timespec, i32>)> {
+        self.leap_second_tables.as_ref().map(|(v1, v2)| {
+            (v1.iter().cloned().collect(), v2.iter().cloned().collect())
+        })
+    }
+
+    /// Get the union of the leap second tables of both blocks (see
+    /// `leap_second_tables`). When both tables contain a leap second at the
+    /// same timestamp the correction of the 64-bit block is used. Without
+    /// both tables available this equals `get_leap_second_transitions`.
+    pub fn merged_leap_seconds(&self) -> BTreeMap<Timespec, i32> {
+        match self.leap_second_tables() {
+            Some((mut v1, v2)) => {
+                v1.extend(v2);
+                v1
+            },
+            None => self.get_leap_second_transitions()
+        }
+    }
+
+    /// Returns the cumulative leap second correction in seconds in effect at
+    /// the provided timestamp: the correction of the last leap second listed
+    /// in the zoneinfo file at or before it (only the `right/` zones, see
+    /// `by_tz_right`, list leap seconds). Like transitions a leap second is in
+    /// effect from its own timestamp onwards.
+    pub fn leap_seconds_at(&self, timestamp: Timespec) -> i32 {
+        let leaps = &self.zone_info.leap_seconds_data;
+        let count = leaps.partition_point(|&(time, _)| time <= timestamp);
+
+        if count == 0 {
+            0
+        }
+        else {
+            leaps[count - 1].1
+        }
+    }
+
+    /// Convert a UTC timestamp into International Atomic Time (TAI), which
+    /// was 10 seconds ahead of UTC in 1972 and runs ahead one more second
+    /// for every leap second (see `leap_seconds_at`).
+    ///
+    /// Returns `None` for zones without leap second table, which is the case
+    /// for all zones except the `right/` ones (see `by_tz_right`). Note that
+    /// the leap second times of those files count the leap seconds as well
+    /// (like their transitions), so they are not on the plain UTC scale of
+    /// the provided timestamp: each of them lies the preceding correction
+    /// later than the UTC moment of that leap second.
+    pub fn utc_to_tai(&self, timestamp: Timespec) -> Option<Timespec> {
+        if self.zone_info.leap_seconds_data.is_empty() {
+            return None;
+        }
+
+        Some(Timespec::new(timestamp.sec + 10 + self.leap_seconds_at(timestamp) as i64, timestamp.nsec))
+    }
+
+    /// Return zone info relevant for the provided timestamp. A transition is
+    /// in effect from its own timestamp onwards, so at the exact moment of a
+    /// transition the zone info of that transition is returned. Timestamps
+    /// are compared with nanosecond precision.
+    ///
+    /// Beyond the last listed transition (see `current_date_source`) the
+    /// offset, daylight saving time and abbreviation are derived from the
+    /// POSIX TZ rule of the file. When that rule is missing or invalid the
+    /// last listed transition remains in effect.
+    ///
+    /// ```rust
+    /// extern crate time;
+    /// extern crate zoneinfo;
+    ///
+    /// use zoneinfo::ZoneInfo;
+    ///
+    /// fn main() {
+    ///     let info = ZoneInfo::get_local_zoneinfo().unwrap();
+    ///
+    ///     let actual = info.get_actual_zoneinfo(time::now_utc().to_timespec()).unwrap();
+    ///
+    ///     // A very Northern/Mid-europe based example ;-)
+    ///     println!("It's {}", if actual.isdst {"Summertime!"} else {"cold :("});
+    /// }
+    /// ```
+    pub fn get_actual_zoneinfo<T: Into<UtcTimestamp>>(&self, timestamp: T) -> Option<ZoneInfoElement> {
+        let timestamp = timestamp.into().0;
+
+        if self.current_date_source(timestamp) == DateSource::PosixRule {
+            if let Some(tz) = self.posix_tz() {
+                return Some(posix_element(tz, tz.isdst_at(timestamp.sec)));
+            }
+        }
+
+        self.type_index_at(timestamp).map(|index| self.element(index))
+    }
+
+    /// Returns the offset to UTC in seconds in effect at the provided
+    /// timestamp, like `get_actual_zoneinfo` but without building the whole
+    /// zone info.
+    pub fn get_offset_at(&self, timestamp: Timespec) -> Option<i32> {
+        if self.current_date_source(timestamp) == DateSource::PosixRule {
+            if let Some(tz) = self.posix_tz() {
+                return Some(match tz.dst {
+                    Some(ref dst) if tz.isdst_at(timestamp.sec) => dst.ut_offset,
+                    _ => tz.std_ut_offset
+                });
+            }
+        }
+
+        self.type_index_at(timestamp).map(|index| self.zone_info.local_times[index].ut_offset)
+    }
+
+    /// Returns the abbreviation (like `CEST`) in effect at the provided
+    /// timestamp, like `get_actual_zoneinfo` but borrowed from the zone info
+    /// instead of copied.
+    pub fn get_abbreviation_at(&self, timestamp: Timespec) -> Option<&str> {
+        if self.current_date_source(timestamp) == DateSource::PosixRule {
+            if let Some(tz) = self.posix_tz() {
+                return Some(match tz.dst {
+                    Some(ref dst) if tz.isdst_at(timestamp.sec) => &dst.abbreviation,
+                    _ => &tz.std_abbreviation
+                });
+            }
+        }
+
+        self.type_index_at(timestamp).map(|index| &self.zone_info.local_times[index].abbreviation[..])
+    }
+
+    /// Returns the zone info in effect before the first transition, chosen
+    /// like the reference `localtime` implementation of the tz project does:
+    /// local time type 0 when no transition uses it, otherwise the closest
+    /// standard time type preceding the type of the first transition when
+    /// that one is daylight saving time, otherwise the first standard time
+    /// type.
+    ///
+    /// `get_actual_zoneinfo` returns `None` for those moments.
+    pub fn pretransition_type(&self) -> Option<ZoneInfoElement> {
+        self.pretransition_index().map(|index| self.element(index))
+    }
+
+    /// returns the index of the local time type of `pretransition_type`
+    fn pretransition_index(&self) -> Option<usize> {
+        let local_times = &self.zone_info.local_times;
+        let types: Vec<usize> = self.zone_info
+                                    .transision_times
+                                    .iter()
+                                    .zip(self.zone_info.transision_types.iter())
+                                    .filter(|&(time, _)| time.sec != i64::MIN)
+                                    .map(|(_, &index)| index as usize)
+                                    .collect();
+
+        if local_times.is_empty() {
+            return None;
+        }
+
+        if !types.contains(&0) {
+            return Some(0);
+        }
+
+        if local_times[types[0]].isdst {
+            if let Some(index) = (0..types[0]).rev().find(|&i| !local_times[i].isdst) {
+                return Some(index);
+            }
+        }
+
+        Some(local_times.iter().position(|t| !t.isdst).unwrap_or(0))
+    }
+
+    /// Returns the NUL terminated strings of the abbreviation table exactly
+    /// as stored in the zoneinfo file, in storage order. Unlike the
+    /// abbreviations of the local time types this includes strings no type
+    /// refers to, while abbreviations sharing the suffix of a stored string
+    /// (like `ST` within `CEST`) are not listed separately.
+    ///
+    /// Bytes which aren't valid UTF-8 are replaced by U+FFFD.
+    pub fn raw_abbreviation_table(&self) -> Vec<String> {
+        let chars = &self.zone_info.abbreviation_chars;
+        if chars.is_empty() {
+            return vec![];
+        }
+
+        // the terminator of the last string doesn't start another one
+        chars.strip_suffix(&[0]).unwrap_or(chars)
+             .split(|&c| c == 0)
+             .map(|abbr| String::from_utf8_lossy(abbr).into_owned())
+             .collect()
+    }
+
+    /// Returns the abbreviation (like `CEST`) relevant for the provided
+    /// timestamp, see `element_at_extrapolated`.
+    pub fn abbreviation_at(&self, ts: Timespec) -> Option<String> {
+        self.element_at_extrapolated(ts).map(|info| info.abbreviation)
+    }
+
+    /// Returns the abbreviation relevant for the provided timestamp (see
+    /// `abbreviation_at`) cut off after at most `max` characters, for
+    /// fixed-width displays. Multibyte characters are never split.
+    pub fn abbreviation_at_truncated(&self, ts: Timespec, max: usize) -> Option<String> {
+        self.get_abbreviation_at(ts).map(|abbreviation| abbreviation.chars().take(max).collect())
+    }
+
+    /// Tells whether the abbreviation relevant for the provided timestamp
+    /// (see `abbreviation_at`) is the expected one, for example whether
+    /// `America/New_York` uses `EDT` in July.
+    ///
+    /// Numeric abbreviations are compared by value: `+03`, `+0300` and the
+    /// quoted POSIX TZ form `<+03>` all match each other. Alphabetic
+    /// abbreviations must match exactly.
+    pub fn abbreviation_matches(&self, ts: Timespec, expected: &str) -> bool {
+        match self.abbreviation_at(ts) {
+            Some(abbreviation) => normalized_abbreviation(&abbreviation) == normalized_abbreviation(expected),
+            None => false
+        }
+    }
+
+    /// Return the zone info relevant for the provided timestamp (see
+    /// `element_at_extrapolated`) as a fixed size `#[repr(C)]` value.
+    pub fn current_state_repr(&self, timestamp: Timespec) -> Option<CurrentState> {
+        let info = self.element_at_extrapolated(timestamp)?;
+        let mut abbr = [0u8; 8];
+        let len = std::cmp::min(info.abbreviation.len(), abbr.len() - 1);
+
+        abbr[..len].copy_from_slice(&info.abbreviation.as_bytes()[..len]);
+
+        Some(CurrentState { offset: info.ut_offset, isdst: info.isdst, abbr })
+    }
+
+    /// Format the zone info relevant for the provided timestamp (see
+    /// `element_at_extrapolated`), replacing these placeholders in `fmt`:
+    ///
+    /// - `%z`: offset to UTC like `+0200`
+    /// - `%o`: offset to UTC in ISO 8601 notation like `+02:00`
+    /// - `%Z`: abbreviation like `CEST`
+    /// - `%%`: a literal `%`
+    ///
+    /// For example `"CEST (+02:00)"` for `"%Z (%o)"`. Offsets with seconds
+    /// (like the local mean time of the 19th century) get the seconds
+    /// appended, like `+001932` and `+00:19:32`. Other text, including
+    /// unknown placeholders, is kept as is. Returns `None` when no zone info
+    /// is available for the timestamp.
+    pub fn format_at(&self, ts: Timespec, fmt: &str) -> Option<String> {
+        let info = self.element_at_extrapolated(ts)?;
+        let basic = offset_string(info.ut_offset, "");
+        let extended = offset_string(info.ut_offset, ":");
+
+        let mut result = String::new();
+        let mut chars = fmt.chars();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                result.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('z') => result.push_str(&basic),
+                Some('o') => result.push_str(&extended),
+                Some('Z') => result.push_str(&info.abbreviation),
+                Some('%') => result.push('%'),
+                Some(other) => {
+                    result.push('%');
+                    result.push(other);
+                },
+                None => result.push('%')
+            }
+        }
+
+        Some(result)
+    }
+
+    /// Returns the offset to UTC in seconds at the Unix epoch
+    /// (1970-01-01T00:00:00Z), a common reference point for comparisons.
+    pub fn offset_at_epoch(&self) -> Option<i32> {
+        self.get_offset_at(Timespec::new(0, 0))
+    }
+
+    /// Tells whether daylight saving time is in effect at the provided
+    /// timestamp, or `None` when no zone information is available for it.
+    pub fn is_dst_at(&self, timestamp: Timespec) -> Option<bool> {
+        if self.current_date_source(timestamp) == DateSource::PosixRule {
+            if let Some(tz) = self.posix_tz() {
+                return Some(tz.isdst_at(timestamp.sec));
+            }
+        }
+
+        self.type_index_at(timestamp)
+            .map(|index| self.zone_info.local_times[index].isdst)
+    }
+
+    /// Returns the standard time offset to UTC in seconds around the
+    /// provided timestamp, whether or not daylight saving time is in effect,
+    /// for example to compute the daylight saving time savings as the actual
+    /// offset minus this one. During daylight saving time this is the offset
+    /// of the closest preceding standard time transition (or the following
+    /// one when there is none), beyond the last listed transition the
+    /// standard time offset of the POSIX TZ rule.
+    ///
+    /// Returns `None` when no zone information is available for the
+    /// timestamp, or when the zone never observes standard time nearby.
+    pub fn get_standard_offset_at(&self, timestamp: Timespec) -> Option<i32> {
+        let info = self.get_actual_zoneinfo(timestamp)?;
+        if !info.isdst {
+            return Some(info.ut_offset);
+        }
+
+        if self.current_date_source(timestamp) == DateSource::PosixRule {
+            if let Some(tz) = self.posix_tz() {
+                return Some(tz.std_ut_offset);
+            }
+        }
+
+        let local_times = &self.zone_info.local_times;
+        let types = &self.zone_info.transision_types;
+        let count = self.transitions_up_to(timestamp);
+        types[..count].iter()
+                      .rev()
+                      .chain(types[count..].iter())
+                      .map(|&index| &local_times[index as usize])
+                      .find(|t| !t.isdst)
+                      .map(|t| t.ut_offset)
+    }
+
+    /// Returns how many seconds daylight saving time adds to the offset to
+    /// UTC at the provided timestamp: the actual offset minus the standard
+    /// offset (see `get_standard_offset_at`). Usually this is 3600 during
+    /// daylight saving time and 0 otherwise; zones with "negative" daylight
+    /// saving time like `Europe/Dublin` report negative savings.
+    pub fn dst_savings_at(&self, timestamp: Timespec) -> Option<i32> {
+        Some(self.get_offset_at(timestamp)? - self.get_standard_offset_at(timestamp)?)
+    }
+
+    /// Returns the standard time offset to UTC in seconds of the current era:
+    /// the one of the POSIX TZ rule, or for zoneinfo files without such rule
+    /// the one around the last listed transition (see
+    /// `get_standard_offset_at`).
+    pub fn standard_offset(&self) -> Option<i32> {
+        match self.posix_tz() {
+            Some(tz) => Some(tz.std_ut_offset),
+            None => self.get_standard_offset_at(self.latest_transition()?)
+        }
+    }
+
+    /// Tells whether the zone has any daylight saving time local time type
+    /// or a POSIX TZ rule defining daylight saving time, as a cheap check
+    /// before using daylight saving time specific functions. Unlike
+    /// `observes_dst` this includes types no transition refers to.
+    pub fn has_dst(&self) -> bool {
+        self.zone_info.local_times.iter().any(|t| t.isdst)
+            || self.posix_tz().is_some_and(|tz| tz.dst.is_some())
+    }
+
+    /// Tells whether this zone ever observed daylight saving time, according
+    /// to the listed transitions or the POSIX TZ rule.
+    pub fn observes_dst(&self) -> bool {
+        let listed = self.zone_info
+                         .transision_types
+                         .iter()
+                         .any(|&index| self.zone_info.local_times[index as usize].isdst);
+
+        listed || self.posix_tz().is_some_and(|tz| tz.dst.is_some())
+    }
+
+    /// Tells whether standard (non daylight saving) time is in effect at the
+    /// provided timestamp, or `None` when no zone information is available
+    /// for it.
+    ///
+    /// This reflects the `isdst` flag of the zoneinfo file as is. Note that
+    /// some zones use "negative" daylight saving time: `Europe/Dublin` for
+    /// example treats its summer time (IST) as standard time and flags the
+    /// winter time (GMT) as daylight saving time.
+    pub fn is_standard_time_at(&self, timestamp: Timespec) -> Option<bool> {
+        self.is_dst_at(timestamp).map(|isdst| !isdst)
+    }
+
+    /// Returns as a tuple a timestamp and related information when the next transaction will take
+    /// place, strictly after the provided timestamp. At the exact moment of a transition that
+    /// transition is already in effect (see `get_actual_zoneinfo`), so the one after it is
+    /// returned.
+    ///
+    /// Beyond the last listed transition the next transition is computed from the POSIX TZ rule.
+    ///
+    /// Note that in some regions there is no DST, and this function will return None.
+    pub fn get_next_transition_time(&self, timestamp: Timespec) -> Option<(Timespec, ZoneInfoElement)> {
+        let count = self.transitions_up_to(timestamp);
+
+        if let Some(&time) = self.zone_info.transision_times.get(count) {
+            Some((time, self.element(self.zone_info.transision_types[count] as usize)))
+        }
+        else {
+            self.extrapolated_transitions(timestamp)
+                .into_iter()
+                .find(|&(time, _)| time > timestamp)
+        }
+    }
+
+    /// Returns as a tuple the timestamp and related information of the last transition taking
+    /// place strictly before the provided timestamp, like when daylight saving time started.
+    /// Beyond the last listed transition the transitions of the POSIX TZ rule are taken into
+    /// account.
+    ///
+    /// Returns None before the first transition listed in the zoneinfo file; the initial
+    /// `std::i64::MIN` transition (see `get_transitions`) is never returned.
+    pub fn get_previous_transition_time(&self, timestamp: Timespec) -> Option<(Timespec, ZoneInfoElement)> {
+        if let Some(found) = self.extrapolated_transitions(timestamp)
+                                 .into_iter()
+                                 .rev()
+                                 .find(|&(time, _)| time < timestamp) {
+            return Some(found);
+        }
+
+        let times = &self.zone_info.transision_times;
+        let count = times.partition_point(|&time| time < timestamp);
+        if count == 0 || times[count - 1].sec == i64::MIN {
+            return None;
+        }
+
+        Some((times[count - 1], self.element(self.zone_info.transision_types[count - 1] as usize)))
+    }
+
+    /// Returns how long it takes from the provided timestamp until the next transition (see
+    /// `get_next_transition_time`), for example to sleep until the next clock change.
+    ///
+    /// Returns None when no transition follows, like for zones without DST or beyond the last
+    /// listed transition of zoneinfo files without a POSIX TZ rule, and when the duration doesn't
+    /// fit a `Duration`.
+    pub fn duration_until_next_transition(&self, timestamp: Timespec) -> Option<Duration> {
+        let (time, _) = self.get_next_transition_time(timestamp)?;
+
+        // `Duration` holds up to `i64::MAX` milliseconds
+        if time.sec.checked_sub(timestamp.sec)? >= Duration::max_value().num_seconds() {
+            return None;
+        }
+
+        Some(time - timestamp)
+    }
+
+    /// Same as `get_actual_zoneinfo`, but taking plain seconds since the Unix
+    /// epoch so callers don't need to depend on the `time` crate.
+    pub fn zoneinfo_at_secs(&self, secs: i64) -> Option<ZoneInfoElement> {
+        self.get_actual_zoneinfo(Timespec::new(secs, 0))
+    }
+
+    /// Returns the UTC offset in seconds in effect at the provided number of
+    /// seconds since the Unix epoch, see `zoneinfo_at_secs`.
+    pub fn offset_at_secs(&self, secs: i64) -> Option<i32> {
+        self.get_offset_at(Timespec::new(secs, 0))
+    }
+
+    /// Same as `is_dst_at`, but taking plain seconds since the Unix epoch.
+    pub fn is_dst_at_secs(&self, secs: i64) -> Option<bool> {
+        self.is_dst_at(Timespec::new(secs, 0))
+    }
+
+    /// Same as `get_next_transition_time`, but taking and returning plain
+    /// seconds since the Unix epoch.
+    pub fn next_transition_secs(&self, secs: i64) -> Option<(i64, ZoneInfoElement)> {
+        self.get_next_transition_time(Timespec::new(secs, 0))
+            .map(|(time, info)| (time.sec, info))
+    }
+
+    /// Returns up to `n` transitions taking place at or after `from`, ordered
+    /// by time, for example to schedule work around upcoming clock changes.
+    /// Transitions which don't change the offset, daylight saving time or
+    /// abbreviation are left out.
+    ///
+    /// Beyond the last listed transition the transitions are computed from
+    /// the POSIX TZ rule (see `element_at_extrapolated`), for at most 10000
+    /// years.
+    pub fn upcoming_transitions(&self, from: Timespec, n: usize) -> Vec<(Timespec, ZoneInfoElement)> {
+        let mut result = vec![];
+        let mut previous = None;
+
+        for (&time, info) in self.transition_map() {
+            if time >= from && is_change(&previous, info) {
+                result.push((time, info.clone()));
+            }
+            previous = Some(info.clone());
+        }
+        result.truncate(n);
+
+        let tz = match self.posix_tz() {
+            Some(tz) => tz,
+            None => return result
+        };
+        if tz.dst.is_none() {
+            return result;
+        }
+        let after = self.tabulated_range().map_or(i64::MIN, |(_, last)| last.sec);
+        let first_year = posix_year(std::cmp::max(from.sec, after));
+        let years = std::cmp::min(n, MAX_EXTRAPOLATED_YEARS as usize) as i64;
+
+        // daylight saving time rules switch at least once a year
+        let mut year = first_year;
+        while result.len() < n && year <= first_year + years {
+            for (time, isdst) in posix::transitions_in_years(tz, year..year + 1) {
+                if time <= after {
+                    continue;
+                }
+                let info = posix_element(tz, isdst);
+                if result.len() < n && time >= from.sec && is_change(&previous, &info) {
+                    result.push((Timespec::new(time, 0), info.clone()));
+                }
+                previous = Some(info);
+            }
+            year += 1;
+        }
+
+        result
+    }
+
+    /// Returns how many seconds the wall clock jumps at the transition taking
+    /// place at exactly the provided timestamp: positive when the clock is
+    /// set forward (local times are skipped), negative when the clock is set
+    /// back (local times are repeated).
+    ///
+    /// Returns `None` when no transition takes place at that timestamp or
+    /// when it's the first known transition. Beyond the last listed
+    /// transition the transitions of the POSIX TZ rule are taken into
+    /// account.
+    pub fn wall_clock_jump_at(&self, timestamp: Timespec) -> Option<i32> {
+        let index = match self.zone_info.transision_times.binary_search(&timestamp) {
+            Ok(0) => return None,
+            Ok(index) => index,
+            Err(_) => {
+                if !self.extrapolated_transitions(timestamp).iter().any(|&(time, _)| time == timestamp) {
+                    return None;
+                }
+                let before = self.get_offset_at(timestamp - Duration::nanoseconds(1))?;
+
+                return Some(self.get_offset_at(timestamp)? - before);
+            }
+        };
+
+        let types = &self.zone_info.transision_types;
+        let before = &self.zone_info.local_times[types[index - 1] as usize];
+        let after = &self.zone_info.local_times[types[index] as usize];
+
+        Some(after.ut_offset - before.ut_offset)
+    }
+
+    /// Convert a local wall clock time (expressed as if it was a UTC
+    /// timestamp) into the moment(s) in UTC at which that wall clock time
+    /// occurred in this zone.
+    ///
+    /// When the clock was set forward the skipped local times map to
+    /// `LocalResult::None`, when the clock was set back the repeated local
+    /// times map to `LocalResult::Ambiguous`. Beyond the last listed
+    /// transition the POSIX TZ rule is followed (see `get_actual_zoneinfo`).
+    pub fn local_to_utc<T: Into<LocalTimestamp>>(&self, local: T) -> LocalResult<Timespec> {
+        let local = local.into().0;
+        // offsets are always less than a day and transitions are much further
+        // apart, so only the offsets in effect a day before and after matter
+        let mut candidates = vec![];
+
+        // probes beyond the range of timestamps are skipped
+        for probe in [local.sec.checked_sub(86400), local.sec.checked_add(86400)].iter().flatten() {
+            let offset = match self.get_offset_at(Timespec::new(*probe, 0)) {
+                Some(offset) => offset,
+                None => continue
+            };
+            let utc = match local.sec.checked_sub(offset as i64) {
+                Some(utc) => Timespec::new(utc, local.nsec),
+                None => continue
+            };
+            let valid = self.get_offset_at(utc) == Some(offset);
+
+            if valid && !candidates.contains(&utc) {
+                candidates.push(utc);
+            }
+        }
+
+        candidates.sort();
+
+        match candidates.len() {
+            0 => LocalResult::None,
+            1 => LocalResult::Single(candidates[0]),
+            _ => LocalResult::Ambiguous(candidates[0], candidates[1])
+        }
+    }
+
+    /// Tells whether the provided local wall clock time was skipped because
+    /// the clock was set forward, like 02:30 on a spring forward night (see
+    /// `local_to_utc`).
+    pub fn is_local_time_gap(&self, local: Timespec) -> bool {
+        self.local_to_utc(local) == LocalResult::None
+    }
+
+    /// Tells whether the provided local wall clock time occurred twice
+    /// because the clock was set back (see `local_to_utc`).
+    pub fn is_local_time_overlap(&self, local: Timespec) -> bool {
+        matches!(self.local_to_utc(local), LocalResult::Ambiguous(_, _))
+    }
+
+    /// Convert a local wall clock time into UTC like `local_to_utc`, but
+    /// resolve an ambiguous local time into a single moment.
+    ///
+    /// Following the `fold` semantics of PEP 495, `fold == false` picks the
+    /// earlier moment (before the clock was set back) and `fold == true`
+    /// picks the later one. Skipped local times return `None`.
+    pub fn local_to_utc_with_fold(&self, local: Timespec, fold: bool) -> Option<Timespec> {
+        match self.local_to_utc(local) {
+            LocalResult::None => None,
+            LocalResult::Single(utc) => Some(utc),
+            LocalResult::Ambiguous(earlier, later) => Some(if fold {later} else {earlier})
+        }
+    }
+
+    /// Convert a local wall clock time to UTC (picking the earlier moment for
+    /// repeated local times) and back to local time again, to check the
+    /// conversions against each other.
+    ///
+    /// For existing local times the result equals the input, also for
+    /// repeated ones. Skipped local times (see `local_to_utc`) return `None`.
+    pub fn roundtrip_local(&self, local: Timespec) -> Option<Timespec> {
+        let utc = self.local_to_utc_with_fold(local, false)?;
+        let offset = self.get_actual_zoneinfo(utc)?.ut_offset;
+
+        Some(Timespec::new(utc.sec + offset as i64, utc.nsec))
+    }
+
+    /// Convert a local calendar date and wall clock time in this zone into
+    /// UTC, for example what "2025-03-30 02:30 in Europe/Amsterdam" means.
+    ///
+    /// See `local_to_utc` for the handling of skipped and repeated local
+    /// times. Fields out of range (like February 30th or hour 24) result in
+    /// `LocalResult::None`.
+    pub fn resolve_local(&self, year: i32, month: u32, day: u32,
+                         hour: u32, min: u32, sec: u32) -> LocalResult<Timespec> {
+        match local_seconds(year as i64, month, day, hour, min, sec) {
+            Some(local) => self.local_to_utc(Timespec::new(local, 0)),
+            None => LocalResult::None
+        }
+    }
+
+    /// Convert a broken-down `time::Tm` holding a local wall clock time in
+    /// this zone into UTC, like `resolve_local`. The `tm_utcoff`, `tm_isdst`,
+    /// `tm_wday` and `tm_yday` fields are ignored; the offset is taken from
+    /// the zone. Nanoseconds are preserved.
+    pub fn tm_local_to_utc(&self, tm: &time::Tm) -> LocalResult<Timespec> {
+        if tm.tm_mon < 0 || tm.tm_mday < 0 || tm.tm_hour < 0 || tm.tm_min < 0 || tm.tm_sec < 0 ||
+           tm.tm_nsec < 0 {
+            return LocalResult::None;
+        }
+
+        match local_seconds(tm.tm_year as i64 + 1900, tm.tm_mon as u32 + 1, tm.tm_mday as u32,
+                            tm.tm_hour as u32, tm.tm_min as u32, tm.tm_sec as u32) {
+            Some(local) => self.local_to_utc(Timespec::new(local, tm.tm_nsec)),
+            None => LocalResult::None
+        }
+    }
+
+    /// Returns all transitions taking place from `start` (inclusive) until
+    /// `end` (exclusive), ordered by time.
+    pub fn transitions_between(&self, start: Timespec, end: Timespec) -> Vec<(Timespec, ZoneInfoElement)> {
+        self.get_transitions_in_range(start, end)
+    }
+
+    /// Returns the transitions listed in the zoneinfo file with
+    /// `start <= time < end`, ordered by time, for example to mark the clock
+    /// changes in a calendar view. The bounds are found by binary search.
+    ///
+    /// The initial `std::i64::MIN` timestamp (see `get_transitions`) is only
+    /// included when `start` is `std::i64::MIN` as well.
+    pub fn get_transitions_in_range(&self, start: Timespec, end: Timespec) -> Vec<(Timespec, ZoneInfoElement)> {
+        let times = &self.zone_info.transision_times;
+        let first = times.partition_point(|&time| time < start);
+        let last = times.partition_point(|&time| time < end);
+
+        (first..std::cmp::max(first, last))
+            .map(|i| (times[i], self.element(self.zone_info.transision_types[i] as usize)))
+            .collect()
+    }
+
+    /// Returns the distinct offsets to UTC in seconds (sorted) in effect at
+    /// any moment from `start` (inclusive) until `end` (exclusive), like the
+    /// offsets `Europe/Amsterdam` used during the 1940s. The offset in effect
+    /// at `start` is included even when no transition takes place in the
+    /// window; before the first transition that is the offset of
+    /// `pretransition_type`.
+    ///
+    /// Beyond the last listed transition the transitions of the POSIX TZ rule
+    /// are taken into account.
+    pub fn offsets_in_range(&self, start: Timespec, end: Timespec) -> Vec<i32> {
+        let mut offsets = vec![];
+        if start >= end {
+            return offsets;
+        }
+
+        match self.get_offset_at(start) {
+            Some(offset) => offsets.push(offset),
+            None => offsets.extend(self.pretransition_type().map(|info| info.ut_offset))
+        }
+        offsets.extend(self.transitions_between(start, end).into_iter().map(|(_, info)| info.ut_offset));
+
+        let mut at = match self.latest_transition() {
+            Some(last) if last > start => last,
+            _ => start
+        };
+        while let Some((time, info)) = self.get_next_transition_time(at) {
+            if time >= end {
+                break;
+            }
+            offsets.push(info.ut_offset);
+            at = time;
+        }
+
+        offsets.sort();
+        offsets.dedup();
+        offsets
+    }
+
+    /// Returns all transitions taking place during the provided local
+    /// calendar date in this zone, ordered by time; for example to mark the
+    /// days with a clock change in a calendar.
+    ///
+    /// A transition belongs to the date shown by the wall clock at the moment
+    /// of the transition, before the clock changes (so a transition from
+    /// 00:00 to 23:00 the day before belongs to the later date). Invalid dates
+    /// (like February 30th) have no transitions. Beyond the last listed
+    /// transition the POSIX TZ rule is followed.
+    pub fn transitions_on_local_date(&self, year: i32, month: u32, day: u32) -> Vec<(Timespec, ZoneInfoElement)> {
+        if !(1..=12).contains(&month) || day < 1 || day > civil::days_in_month(year as i64, month) {
+            return vec![];
+        }
+
+        let local_start = civil::days_from_civil(year as i64, month, day) * 86400;
+        let local_end = local_start + 86400;
+
+        // offsets are always less than a day, so the local day lies within a
+        // day of the same range in UTC
+        let transitions = self.extrapolated_transitions_between(Timespec::new(local_start - 86400, 0),
+                                                                Timespec::new(local_end + 86400, 0));
+        let mut previous = self.get_offset_at(Timespec::new(local_start - 86400 - 1, 999_999_999))
+                               .or_else(|| self.pretransition_type().map(|info| info.ut_offset));
+        let mut result = vec![];
+
+        for (time, info) in transitions {
+            let offset = previous.unwrap_or(info.ut_offset) as i64;
+            let local = time.sec + offset;
+
+            previous = Some(info.ut_offset);
+            if local >= local_start && local < local_end {
+                result.push((time, info));
+            }
+        }
+
+        result
+    }
+
+    /// Returns the transitions taking place from `window` before until
+    /// `window` after the current time, ordered by time, for example to show
+    /// recent and upcoming clock changes. Transitions which don't change the
+    /// offset, daylight saving time or abbreviation are left out, so zones
+    /// without daylight saving time mostly return nothing. Beyond the last
+    /// listed transition the POSIX TZ rule is followed.
+    pub fn transitions_within(&self, window: Duration) -> Vec<(Timespec, ZoneInfoElement)> {
+        self.transitions_around(time::get_time(), window)
+    }
+
+    /// returns the transitions within `window` around `now`, see
+    /// `transitions_within`
+    fn transitions_around(&self, now: Timespec, window: Duration) -> Vec<(Timespec, ZoneInfoElement)> {
+        let start = now - window;
+        let mut previous = self.get_actual_zoneinfo(start - Duration::nanoseconds(1));
+        let mut result = vec![];
+
+        for (time, info) in self.extrapolated_transitions_between(start, now + window) {
+            if is_change(&previous, &info) {
+                result.push((time, info.clone()));
+            }
+            previous = Some(info);
+        }
+
+        result
+    }
+
+    /// Returns the fraction (0.0 to 1.0) of the provided year during which
+    /// daylight saving time was active. The year runs from January 1st until
+    /// December 31st in UTC.
+    ///
+    /// Zones without daylight saving time return 0.0. `None` is returned when
+    /// the zoneinfo holds no information at all until the end of that year.
+    /// Beyond the last listed transition the POSIX TZ rule is followed.
+    pub fn dst_fraction_of_year(&self, year: i32) -> Option<f64> {
+        let start = Timespec::new(civil::days_from_civil(year as i64, 1, 1) * 86400, 0);
+        let end = Timespec::new(civil::days_from_civil(year as i64 + 1, 1, 1) * 86400, 0);
+
+        self.get_actual_zoneinfo(end)?;
+
+        let mut isdst = match self.get_actual_zoneinfo(start) {
+            Some(info) => info.isdst,
+            None => false
+        };
+        let mut since = start.sec;
+        let mut dst_seconds = 0;
+
+        for (time, info) in self.extrapolated_transitions_between(start, end) {
+            if isdst {
+                dst_seconds += time.sec - since;
+            }
+            since = time.sec;
+            isdst = info.isdst;
+        }
+
+        if isdst {
+            dst_seconds += end.sec - since;
+        }
+
+        Some(dst_seconds as f64 / (end.sec - start.sec) as f64)
+    }
+
+    /// Returns the magic bytes of the header exactly as found in the file,
+    /// which is always `TZif` since files with other magic bytes are refused
+    /// (see `ZoneInfoError::InvalidMagic`).
+    pub fn raw_magic(&self) -> [u8; 4] {
+        self.zone_info.header.tzh_magic
+    }
+
+    /// Returns the version of the zoneinfo file. Only version 2 files and
+    /// later carry 64-bit data and a POSIX TZ string, so for version 1 files
+    /// `get_dst_specifier` returns an empty string. Zone info built out of a
+    /// POSIX TZ string reports `TzVersion::V2`.
+    pub fn version(&self) -> TzVersion {
+        match self.zone_info.header.tzh_version {
+            '2' => TzVersion::V2,
+            '3' => TzVersion::V3,
+            '4' => TzVersion::V4,
+            _ => TzVersion::V1
+        }
+    }
+
+    /// Returns the version byte of the header exactly as found in the file
+    /// (`0`, `b'2'`, `b'3'` or `b'4'` as of 2022).
+    pub fn raw_version_byte(&self) -> u8 {
+        self.zone_info.header.tzh_version as u8
+    }
+
+    /// Returns the sizes of the sections of the data block in use. For
+    /// version 2 files and later this is the 64-bit block (unless
+    /// `ParseOptions::prefer_64bit` is unset); the sizes of the other block
+    /// are not included.
+    pub fn section_sizes(&self) -> SectionSizes {
+        self.zone_info.header.section_sizes()
+    }
+
+    /// Returns the timestamps of the first and the last transition listed in
+    /// the zoneinfo file, or `None` when the file lists no transitions.
+    pub fn tabulated_range(&self) -> Option<(Timespec, Timespec)> {
+        let mut times = self.zone_info
+                            .transision_times
+                            .iter()
+                            .filter(|time| time.sec != i64::MIN);
+        let first = *times.next()?;
+        let last = *times.next_back().unwrap_or(&first);
+
+        Some((first, last))
+    }
+
+    /// Tells whether the provided timestamp lies before the first transition
+    /// listed in the zoneinfo file, so the zone info for it comes from the
+    /// type in effect before any transition (see `pretransition_type`) rather
+    /// than from the table. Such results are approximate at best: tzdata
+    /// doesn't track history before 1970 for many zones.
+    ///
+    /// Zones listing no transitions at all are never prehistoric.
+    pub fn is_prehistoric(&self, ts: Timespec) -> bool {
+        self.tabulated_range().is_some_and(|(first, _)| ts < first)
+    }
+
+    /// Returns the timestamp of the last transition listed in the zoneinfo
+    /// file, or `None` when the file lists no transitions.
+    ///
+    /// Beyond this moment the zone info is extrapolated from the POSIX TZ
+    /// rule (see `current_date_source`). Zones without such rule (version 1
+    /// files) keep the last listed zone info forever, so when their last
+    /// transition lies in the past while the zone still observes daylight
+    /// saving time the answers for current dates are wrong. Comparing this
+    /// timestamp to the current time helps to detect stale or truncated
+    /// tzdata.
+    pub fn latest_transition(&self) -> Option<Timespec> {
+        self.tabulated_range().map(|(_, last)| last)
+    }
+
+    /// Tells whether zone information for `now` is backed by the transitions
+    /// listed in the zoneinfo file or has to be extrapolated from the POSIX TZ
+    /// rule (see `get_dst_specifier`).
+    ///
+    /// Zoneinfo files without a POSIX TZ rule (version 1 files) keep using
+    /// the last listed transition and always report `DateSource::Table`.
+    pub fn current_date_source(&self, now: Timespec) -> DateSource {
+        // borrowed instead of `get_dst_specifier`, this is on the lookup path
+        if self.time_zone_specifier.trim().is_empty() {
+            return DateSource::Table;
+        }
+
+        match self.tabulated_range() {
+            Some((_, last)) if now <= last => DateSource::Table,
+            _ => DateSource::PosixRule
+        }
+    }
+
+    /// Return zone info relevant for the provided timestamp, derived from
+    /// the POSIX TZ rule beyond the last listed transition.
+    ///
+    /// Same as `get_actual_zoneinfo`, which extrapolates the same way.
+    pub fn element_at_extrapolated(&self, timestamp: Timespec) -> Option<ZoneInfoElement> {
+        self.get_actual_zoneinfo(timestamp)
+    }
+
+    /// Returns the moment daylight saving time ended for good: the last
+    /// transition to standard time, when no transition to daylight saving
+    /// time follows and the POSIX TZ rule doesn't define daylight saving time
+    /// either.
+    ///
+    /// Returns `None` for zones still observing daylight saving time and for
+    /// zones that never did.
+    pub fn dst_abolished_at(&self) -> Option<Timespec> {
+        if let Some(tz) = self.posix_tz() {
+            if tz.dst.is_some() {
+                return None;
+            }
+        }
+
+        let transitions = self.transition_map();
+        let (last_dst, _) = transitions.iter().rev().find(|&(_, info)| info.isdst)?;
+
+        transitions.range(*last_dst..)
+                   .find(|&(_, info)| !info.isdst)
+                   .map(|(time, _)| *time)
+    }
+
+    /// Returns the local hour (0 to 23) at which the wall clock usually is
+    /// changed for daylight saving time, like 2 for `America/New_York` or 1
+    /// for `Europe/London`: the most common hour, on the clock before the
+    /// change, among the last 20 listed transitions between standard and
+    /// daylight saving time. On a tie the earliest hour is returned.
+    ///
+    /// Returns `None` for zones without daylight saving time, also when the
+    /// POSIX TZ rule says daylight saving time is no longer observed.
+    pub fn typical_transition_local_hour(&self) -> Option<u8> {
+        if let Some(tz) = self.posix_tz() {
+            tz.dst.as_ref()?;
+        }
+
+        let transitions: Vec<(&Timespec, &ZoneInfoElement)> = self.transition_map().iter().collect();
+        let mut counts = [0usize; 24];
+
+        for window in transitions.windows(2).rev().filter(|w| w[0].1.isdst != w[1].1.isdst).take(20) {
+            let local = window[1].0.sec + window[0].1.ut_offset as i64;
+            counts[local.rem_euclid(86400) as usize / 3600] += 1;
+        }
+
+        let max = *counts.iter().max()?;
+        if max == 0 {
+            return None;
+        }
+
+        counts.iter().position(|&count| count == max).map(|hour| hour as u8)
+    }
+
+    /// Tells whether the daylight saving time rules of the POSIX TZ rule are
+    /// those of the southern hemisphere: daylight saving time starts in a
+    /// later month than it ends (like October and April for
+    /// `Australia/Sydney`), so summer time spans the turn of the year.
+    ///
+    /// Returns `None` when the POSIX TZ rule is missing or defines no
+    /// daylight saving time.
+    pub fn is_southern_hemisphere_dst(&self) -> Option<bool> {
+        let tz = self.posix_tz()?;
+        let dst = tz.dst.as_ref()?;
+
+        Some(dst.start.date.month() > dst.end.date.month())
+    }
+
+    /// Tells whether the listed transitions agree with the POSIX TZ rule in
+    /// the provided (UTC) years, see `posix_tail_mismatches`.
+    pub fn posix_tail_consistent(&self, check_years: Range<i32>) -> bool {
+        self.posix_tail_mismatches(check_years).is_empty()
+    }
+
+    /// Compare the listed transitions with the transitions computed from the
+    /// POSIX TZ rule in the provided (UTC) years, for example to check the
+    /// output of a tzfile builder: `zic` lists transitions until 2037 which
+    /// should follow the rule of the footer. Only the part of those years
+    /// until the last listed transition is compared. Transitions which don't
+    /// change anything are ignored.
+    ///
+    /// Returns no mismatches when there is no (valid) POSIX TZ rule.
+    pub fn posix_tail_mismatches(&self, check_years: Range<i32>) -> Vec<TailMismatch> {
+        let tz = match self.posix_tz() {
+            Some(tz) => tz,
+            None => return vec![]
+        };
+        let last = match self.latest_transition() {
+            Some(last) => last.sec,
+            None => return vec![]
+        };
+        let start = civil::days_from_civil(check_years.start as i64, 1, 1) * 86400;
+        let end = std::cmp::min(civil::days_from_civil(check_years.end as i64, 1, 1) * 86400, last + 1);
+        if start >= end {
+            return vec![];
+        }
+
+        let mut previous = self.get_actual_zoneinfo(Timespec::new(start - 1, 0));
+        let mut table = BTreeMap::new();
+        for (time, info) in self.transitions_between(Timespec::new(start, 0), Timespec::new(end, 0)) {
+            if is_change(&previous, &info) {
+                table.insert(time.sec, info.clone());
+            }
+            previous = Some(info);
+        }
+
+        let mut posix: BTreeMap<i64, ZoneInfoElement> = tz.transitions(check_years)
+                                                          .into_iter()
+                                                          .filter(|&(time, _)| time < end)
+                                                          .map(|(time, isdst)| (time, posix_element(tz, isdst)))
+                                                          .collect();
+        let mut times: Vec<i64> = table.keys().chain(posix.keys()).cloned().collect();
+        times.sort();
+        times.dedup();
+
+        let mut mismatches = vec![];
+        for time in times {
+            let listed = table.remove(&time);
+            let computed = posix.remove(&time);
+            let same = match (&listed, &computed) {
+                (Some(listed), Some(computed)) => !is_change(&Some(listed.clone()), computed),
+                _ => false
+            };
+
+            if !same {
+                mismatches.push(TailMismatch { time: Timespec::new(time, 0), table: listed, posix: computed });
+            }
+        }
+
+        mismatches
+    }
+
+    /// returns the parsed POSIX TZ rule, if there is a valid one
+    fn posix_tz(&self) -> Option<&PosixTz> {
+        self.posix_tz.as_ref()
+    }
+
+    /// Parses the POSIX TZ string of the zoneinfo file (see
+    /// `get_dst_specifier`) into its standard time and daylight saving time
+    /// parts. Returns `None` when the file has no such string, as is the case
+    /// for version 1 files.
+    pub fn parse_dst_specifier(&self) -> Option<Result<PosixTz, ZoneInfoError>> {
+        let spec = self.get_dst_specifier();
+
+        if spec.is_empty() {
+            None
+        }
+        else {
+            Some(PosixTz::parse(&spec))
+        }
+    }
+
+    /// Returns a copy of this zone info with an additional transition at
+    /// `at` to the provided offset, abbreviation and daylight saving time
+    /// flag, for example to hotfix a known wrong transition of a deployed
+    /// tzdata release without rebuilding it.
+    ///
+    /// An existing transition at exactly the same instant is shadowed by the
+    /// override; other transitions are kept. The override is in effect until
+    /// the next listed transition. Overrides at or beyond the last listed
+    /// transition last until the next transition of the POSIX TZ rule, which
+    /// is listed as well so the rule takes over again from there. When the
+    /// rule has no such transition (no daylight saving time) the rule is
+    /// dropped and the override stays in effect for good.
+    ///
+    /// Fails with `ZoneInfoError::Malformed` when the override needs another
+    /// local time type while all 256 types a zoneinfo file can hold are in
+    /// use.
+    pub fn with_override(&self, at: Timespec, offset: i32, abbr: &str, isdst: bool)
+                         -> Result<ZoneInfo, ZoneInfoError> {
+        // the POSIX TZ rule governs everything after the last listed transition
+        let resumption = match self.posix_tz() {
+            Some(tz) if self.tabulated_range().is_none_or(|(_, last)| at >= last) => {
+                let year = posix_year(at.sec);
+                let next = posix::transitions_in_years(tz, year..year + 2)
+                             .into_iter()
+                             .find(|&(time, _)| time > at.sec);
+                Some(next.map(|(time, isdst)| (Timespec::new(time, 0), posix_element(tz, isdst))))
+            },
+            _ => None
+        };
+
+        let mut zone = self.clone();
+        let index = zone.local_time_type(offset, abbr, isdst)?;
+        zone.insert_transition(at, index);
+
+        match resumption {
+            Some(Some((time, info))) => {
+                let index = zone.local_time_type(info.ut_offset, &info.abbreviation, info.isdst)?;
+                zone.insert_transition(time, index);
+            },
+            Some(None) => {
+                zone.time_zone_specifier = String::new();
+                zone.posix_tz = None;
+            },
+            None => {}
+        }
+
+        zone.transition_cache = OnceLock::new();
+        Ok(zone)
+    }
+
+    /// returns the index of the local time type with the provided offset,
+    /// abbreviation and daylight saving time flag, adding it when missing
+    fn local_time_type(&mut self, offset: i32, abbr: &str, isdst: bool) -> Result<u8, ZoneInfoError> {
+        let inner = &mut self.zone_info;
+
+        if let Some(index) = inner.local_times.iter().position(|t| {
+            t.ut_offset == offset && t.isdst == isdst && t.abbreviation == abbr
+        }) {
+            return Ok(index as u8);
+        }
+        if inner.local_times.len() >= 256 {
+            return Err(ZoneInfoError::Malformed("all 256 local time types are in use".to_string()));
+        }
+
+        inner.local_times.push(TzType {
+            ut_offset: offset,
+            isdst,
+            abbreviation: abbr.to_string(),
+        });
+        inner.abbreviation_chars.extend(abbr.bytes());
+        inner.abbreviation_chars.push(0);
+        inner.header.tzh_typecnt += 1;
+        inner.header.tzh_charcnt = inner.abbreviation_chars.len() as u32;
+
+        Ok((inner.local_times.len() - 1) as u8)
+    }
+
+    /// adds a transition at `at` to the local time type with the provided
+    /// index, replacing the type of an existing transition at that instant
+    fn insert_transition(&mut self, at: Timespec, index: u8) {
+        let inner = &mut self.zone_info;
+
+        match inner.transision_times.binary_search(&at) {
+            Ok(position) => inner.transision_types[position] = index,
+            Err(position) => {
+                inner.transision_times.insert(position, at);
+                inner.transision_types.insert(position, index);
+                inner.header.tzh_timecnt += 1;
+            }
+        }
+    }
+
+    /// Returns a copy of this zone info without the transitions before
+    /// `since`, like `zic -b slim` trims history, for example to shrink zones
+    /// for embedded use. The local time type in effect at `since` becomes the
+    /// initial type, so the zone info for `since` and later is unchanged
+    /// while earlier moments report that initial type as well. The POSIX TZ
+    /// rule and the leap seconds are kept.
+    pub fn minimize(&self, since: Timespec) -> ZoneInfo {
+        let mut zone = self.clone();
+        let initial = match self.type_index_at(since) {
+            Some(index) => index as u8,
+            None => return zone
+        };
+
+        let inner = &mut zone.zone_info;
+        let (times, types): (Vec<Timespec>, Vec<u8>) = inner.transision_times
+                                                            .iter()
+                                                            .cloned()
+                                                            .zip(inner.transision_types.iter().cloned())
+                                                            .filter(|&(time, _)| time > since)
+                                                            .unzip();
+
+        inner.transision_times = vec![Timespec::new(i64::MIN, 0)];
+        inner.transision_times.extend(times);
+        inner.transision_types = vec![initial];
+        inner.transision_types.extend(types);
+        inner.header.tzh_timecnt = inner.transision_times.len() as u32;
+
+        zone.transition_cache = OnceLock::new();
+        zone
+    }
+
+    /// Tells whether several local time types share the same offset,
+    /// daylight saving time flag and abbreviation, see `dedup_types`.
+    pub fn has_redundant_types(&self) -> bool {
+        let local_times = &self.zone_info.local_times;
+
+        (0..local_times.len()).any(|i| self.first_identical_type(i) != i)
+    }
+
+    /// Returns a copy of this zone info in which local time types with the
+    /// same offset, daylight saving time flag and abbreviation are collapsed
+    /// into the first of them, for example to normalize zones before
+    /// re-encoding them. The transitions are rewritten accordingly, so the
+    /// zone behaves the same.
+    pub fn dedup_types(&self) -> ZoneInfo {
+        let mut zone = self.clone();
+        let count = self.zone_info.local_times.len();
+        let kept: Vec<usize> = (0..count).filter(|&i| self.first_identical_type(i) == i).collect();
+        let mapping: Vec<u8> = (0..count).map(|i| {
+            kept.binary_search(&self.first_identical_type(i)).unwrap() as u8
+        }).collect();
+
+        let inner = &mut zone.zone_info;
+        for index in inner.transision_types.iter_mut() {
+            *index = mapping[*index as usize];
+        }
+        inner.local_times = kept.iter().map(|&i| self.zone_info.local_times[i].clone()).collect();
+        inner.transition_flags1 = kept.iter().map_while(|&i| self.zone_info.transition_flags1.get(i).cloned()).collect();
+        inner.transition_flags2 = kept.iter().map_while(|&i| self.zone_info.transition_flags2.get(i).cloned()).collect();
+        inner.header.tzh_typecnt = inner.local_times.len() as u32;
+        inner.header.tzh_ttisstdcnt = inner.transition_flags1.len() as u32;
+        inner.header.tzh_ttigmtcnt = inner.transition_flags2.len() as u32;
+
+        zone.transition_cache = OnceLock::new();
+        zone
+    }
+
+    /// returns the index of the first local time type identical to the one
+    /// with the provided index
+    fn first_identical_type(&self, index: usize) -> usize {
+        let local_times = &self.zone_info.local_times;
+        let this = &local_times[index];
+
+        local_times.iter()
+                   .position(|t| t.ut_offset == this.ut_offset && t.isdst == this.isdst
+                                 && t.abbreviation == this.abbreviation)
+                   .unwrap()
+    }
+
+    /// Returns a hash of everything that determines the behavior of this
+    /// zone: the transitions (timestamp, offset, daylight saving time and
+    /// abbreviation), the leap seconds and the POSIX TZ rule. Zones with the
+    /// same fingerprint behave the same.
+    ///
+    /// The hash is only meant for comparisons within a single program run;
+    /// it is not guaranteed to be stable across releases.
+    pub fn fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+
+        for (time, info) in self.transition_map() {
+            time.sec.hash(&mut hasher);
+            info.ut_offset.hash(&mut hasher);
+            info.isdst.hash(&mut hasher);
+            info.abbreviation.hash(&mut hasher);
+        }
+        for (time, correction) in self.get_leap_second_transitions() {
+            time.sec.hash(&mut hasher);
+            correction.hash(&mut hasher);
+        }
+        self.get_dst_specifier().hash(&mut hasher);
+
+        hasher.finish()
+    }
+
+    /// Returns the distinct offsets to UTC in seconds in the order they first
+    /// came into effect, like `[1172, 4772, 4800, 1200, 7200, 3600]` for
+    /// `Europe/Amsterdam`. Zones with the same signature went through the
+    /// same offsets in the same order; this is a coarser but cheaper grouping
+    /// key than `fingerprint`.
+    pub fn offset_signature(&self) -> Vec<i32> {
+        let mut signature = vec![];
+
+        for &type_index in &self.zone_info.transision_types {
+            let offset = self.zone_info.local_times[type_index as usize].ut_offset;
+            if !signature.contains(&offset) {
+                signature.push(offset);
+            }
+        }
+
+        signature
+    }
+
+    /// Returns the location of this zone (like `Europe/Amsterdam`) when it
+    /// was loaded by `ZoneInfo::by_tz`.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_ref().map(|name| &name[..])
+    }
+
+    /// Returns a compact JSON representation of this zone, without depending
+    /// on a serialization library. The format is:
+    ///
+    /// ```text
+    /// {"name":"Europe/Amsterdam","version":"2",
+    ///  "transitions":[{"time":-1693706400,"offset":7200,"isdst":true,"abbreviation":"CEST"},...],
+    ///  "posix_tz":"CET-1CEST,M3.5.0,M10.5.0/3"}
+    /// ```
+    ///
+    /// `name` is `null` when unknown (see `name`), `version` is `"1"` for
+    /// version 1 files and `time` is in seconds since the epoch; the initial
+    /// `std::i64::MIN` timestamp is left out.
+    pub fn to_json(&self) -> String {
+        let name = match self.name {
+            Some(ref name) => json_string(name),
+            None => "null".to_string()
+        };
+        let version = match self.zone_info.header.tzh_version {
+            '\0' => '1',
+            version => version
+        };
+        let transitions: Vec<String> = self.transition_table()
+            .into_iter()
+            .map(|(time, offset, isdst, abbreviation)| {
+                format!("{{\"time\":{},\"offset\":{},\"isdst\":{},\"abbreviation\":{}}}",
+                        time, offset, isdst, json_string(&abbreviation))
+            })
+            .collect();
+
+        format!("{{\"name\":{},\"version\":{},\"transitions\":[{}],\"posix_tz\":{}}}",
+                name, json_string(&version.to_string()), transitions.join(","),
+                json_string(&self.get_dst_specifier()))
+    }
+
+    /// Retrieve the daylight saving time rules for loaded zoneinfo.
+    pub fn get_dst_specifier(&self)->String {
+        self.time_zone_specifier.trim().to_string()
+    }
+}
+
+/// In-memory database of zones, built out of zoneinfo files from any source,
+/// see `TzDatabase::from_entries`
+pub struct TzDatabase {
+    zones: BTreeMap<String, ZoneInfo>,
+    errors: BTreeMap<String, ZoneInfoError>
+}
+
+impl TzDatabase {
+    /// Build a database out of `(location, tzfile(5) contents)` entries, for
+    /// example assets embedded in the application. Entries which can't be
+    /// parsed are left out of the database; their errors are available
+    /// through `errors`.
+    pub fn from_entries<I: IntoIterator<Item=(String, Vec<u8>)>>(entries: I) -> TzDatabase {
+        let mut database = TzDatabase { zones: BTreeMap::new(), errors: BTreeMap::new() };
+
+        for (location, data) in entries {
+            match ZoneInfo::from_bytes(&data[..]) {
+                Ok(mut zone) => {
+                    zone.name = Some(location.clone());
+                    database.errors.remove(&location);
+                    database.zones.insert(location, zone);
+                },
+                Err(err) => {
+                    database.zones.remove(&location);
+                    database.errors.insert(location, err);
+                }
+            }
+        }
+
+        database
+    }
+
+    /// Get the zone info of the provided location, like `ZoneInfo::by_tz`
+    pub fn by_tz(&self, location: &str) -> Option<&ZoneInfo> {
+        self.zones.get(location)
+    }
+
+    /// Retrieve all (sorted) locations of the database
+    pub fn locations(&self) -> Vec<String> {
+        self.zones.keys().cloned().collect()
+    }
+
+    /// Get the parse errors of the entries which were left out, by location
+    pub fn errors(&self) -> &BTreeMap<String, ZoneInfoError> {
+        &self.errors
+    }
+}
+
+#[test]
+fn zoneinfo_clone() {
+    let zi = ZoneInfo::get_local_zoneinfo().unwrap();
+
+    let zic = zi.clone();
+
+    assert_eq!(zi.get_dst_specifier(), zic.get_dst_specifier());
+}
+
+#[test]
+fn zoneinfo_slim_file() {
+    // zic -b slim leaves only a placeholder in the version 1 block
+    let v1 = testdata::Block::new(&[(0, false, "")], &[]);
+    let v2 = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "CEST")],
+                                  &[(3000000000, 1), (3020000000, 0)]);
+    let data = testdata::tzif(b'2', &v1, &v2, "CET-1CEST,M3.5.0,M10.5.0/3");
+
+    let zi = ZoneInfo::from_bytes(&data).unwrap();
+    let transitions: Vec<_> = zi.get_transitions().into_iter().collect();
+
+    assert_eq!(zi.get_dst_specifier(), "CET-1CEST,M3.5.0,M10.5.0/3");
+    assert_eq!(zi.raw_version_byte(), b'2');
+    assert_eq!(transitions.len(), 2);
+    assert_eq!(transitions[0].0, Timespec::new(3000000000, 0));
+    assert_eq!(transitions[0].1.abbreviation, "CEST");
+    assert_eq!(transitions[1].1.abbreviation, "CET");
+}
+
+#[test]
+fn zoneinfo_v1_only_file() {
+    let v1 = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "CEST")],
+                                  &[(1000000000, 1), (1020000000, 0)]);
+    let data = testdata::tzif_v1(&v1);
+
+    let zi = ZoneInfo::from_bytes(&data).unwrap();
+
+    assert_eq!(zi.get_transitions().len(), 2);
+    assert_eq!(zi.get_dst_specifier(), "");
+    assert_eq!(zi.raw_magic(), *b"TZif");
+    assert_eq!(zi.raw_version_byte(), 0);
+}
+
+#[test]
+fn zoneinfo_dst_fraction_of_year() {
+    let zi = ZoneInfo::by_tz("Europe/Amsterdam").unwrap();
+
+    // 2020-03-29T01:00Z until 2020-10-25T01:00Z in a leap year
+    assert_eq!(zi.dst_fraction_of_year(2020), Some(210.0 / 366.0));
+    // 2050-03-27T01:00Z until 2050-10-30T01:00Z, computed from the POSIX TZ rule
+    assert_eq!(zi.dst_fraction_of_year(2050), Some(217.0 / 365.0));
+
+    let zi = ZoneInfo::by_tz("Australia/Sydney").unwrap();
+    // 2050-04-02T16:00Z until 2050-10-01T16:00Z is standard time
+    assert_eq!(zi.dst_fraction_of_year(2050), Some(1.0 - 182.0 / 365.0));
+
+    let utc = ZoneInfo::by_tz("Etc/UTC").unwrap();
+
+    assert_eq!(utc.dst_fraction_of_year(2020), Some(0.0));
+}
+
+#[test]
+fn zoneinfo_duplicate_transition_time() {
+    let v1 = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "CEST")],
+                                  &[(1000000000, 1), (1000000000, 0)]);
+    let data = testdata::tzif_v1(&v1);
+
+    let error = ZoneInfo::from_bytes(&data).err().unwrap();
+
+    assert!(matches!(error, ZoneInfoError::Malformed(_)));
+}
+
+#[test]
+fn zoneinfo_current_date_source() {
+    let v1 = testdata::Block::new(&[(0, false, "")], &[]);
+    let v2 = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "CEST")],
+                                  &[(1000000000, 1), (1020000000, 0)]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif(b'2', &v1, &v2, "CET-1CEST,M3.5.0,M10.5.0/3")).unwrap();
+
+    assert_eq!(zi.tabulated_range(), Some((Timespec::new(1000000000, 0), Timespec::new(1020000000, 0))));
+    assert_eq!(zi.current_date_source(Timespec::new(1010000000, 0)), DateSource::Table);
+    assert_eq!(zi.current_date_source(Timespec::new(1020000000, 0)), DateSource::Table);
+    assert_eq!(zi.current_date_source(Timespec::new(1020000001, 0)), DateSource::PosixRule);
+
+    let zi = ZoneInfo::from_bytes(&testdata::tzif_v1(&v2)).unwrap();
+
+    assert_eq!(zi.current_date_source(Timespec::new(1020000001, 0)), DateSource::Table);
+}
+
+#[test]
+fn zoneinfo_max_bytes() {
+    let v1 = testdata::Block::new(&[(3600, false, "CET")], &[]);
+    let data = testdata::tzif_v1(&v1);
+    let path = std::env::temp_dir().join("zoneinfo_max_bytes");
+    std::fs::write(&path, &data).unwrap();
+
+    let limited = ParseOptions { max_bytes: Some(data.len() - 1), ..ParseOptions::default() };
+    let error = ZoneInfo::new_with_options(&path, &limited).err().unwrap();
+    assert!(matches!(error, ZoneInfoError::FileTooLarge));
+
+    let exact = ParseOptions { max_bytes: Some(data.len()), ..ParseOptions::default() };
+    assert!(ZoneInfo::new_with_options(&path, &exact).is_ok());
+    assert!(ZoneInfo::new_with_options(&path, &ParseOptions { max_bytes: None, ..ParseOptions::default() }).is_ok());
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn zoneinfo_local_to_utc_with_fold() {
+    let zi = ZoneInfo::by_tz("Europe/Amsterdam").unwrap();
+    let oct26 = civil::days_from_civil(2025, 10, 26) * 86400;
+    let mar30 = civil::days_from_civil(2025, 3, 30) * 86400;
+
+    // 02:30 happened twice on 2025-10-26: at 00:30 UTC (CEST) and 01:30 UTC (CET)
+    let local = Timespec::new(oct26 + 9000, 0);
+    assert_eq!(zi.local_to_utc_with_fold(local, false), Some(Timespec::new(oct26 + 1800, 0)));
+    assert_eq!(zi.local_to_utc_with_fold(local, true), Some(Timespec::new(oct26 + 5400, 0)));
+
+    // 02:30 was skipped on 2025-03-30
+    assert_eq!(zi.local_to_utc_with_fold(Timespec::new(mar30 + 9000, 0), false), None);
+
+    // 12:00 CEST is unambiguous
+    let noon = Timespec::new(mar30 + 43200, 0);
+    assert_eq!(zi.local_to_utc_with_fold(noon, true), Some(Timespec::new(mar30 + 36000, 0)));
+}
+
+#[test]
+fn zoneinfo_section_sizes() {
+    let v1 = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "CEST")],
+                                  &[(1000000000, 1), (1020000000, 0)]);
+    let data = testdata::tzif_v1(&v1);
+    let sizes = ZoneInfo::from_bytes(&data).unwrap().section_sizes();
+
+    assert_eq!(sizes.transitions, 10);
+    assert_eq!(sizes.local_time_types, 12);
+    assert_eq!(sizes.abbreviations, 9);
+    assert_eq!(sizes.total(), data.len());
+}
+
+#[test]
+fn zoneinfo_from_posix_tz() {
+    let zi = ZoneInfo::from_posix_tz("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+
+    // the initial entry holds the type in effect before the first transition
+    assert_eq!(zi.get_transitions().len(), 401);
+    assert_eq!(zi.get_dst_specifier(), "CET-1CEST,M3.5.0,M10.5.0/3");
+
+    let options = ParseOptions { extrapolation_years: 2021..2023, ..ParseOptions::default() };
+    let zi = ZoneInfo::from_posix_tz_with_options("CET-1CEST,M3.5.0,M10.5.0/3", &options).unwrap();
+    let march28 = civil::days_from_civil(2021, 3, 28) * 86400;
+
+    assert_eq!(zi.tabulated_range().unwrap().0, Timespec::new(march28 + 3600, 0));
+    assert_eq!(zi.get_transitions().len(), 5);
+    assert_eq!(zi.section_sizes().transitions, 5 * 9);
+    assert_eq!(zi.is_dst_at(Timespec::new(march28 + 3600, 0)), Some(true));
+    assert_eq!(zi.is_dst_at(Timespec::new(march28, 0)), Some(false));
+
+    let zi = ZoneInfo::from_posix_tz("<+0545>-5:45").unwrap();
+    assert_eq!(zi.get_transitions().len(), 1);
+    assert!(ZoneInfo::from_posix_tz("CET-1CEST,M3.5.0").is_err());
+}
+
+#[test]
+fn zoneinfo_dst_abolished_at() {
+    let v1 = testdata::Block::new(&[(0, false, "")], &[]);
+    let v2 = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "CEST")],
+                                  &[(1000000000, 1), (1020000000, 0), (1030000000, 1), (1050000000, 0)]);
+
+    let abolished = ZoneInfo::from_bytes(&testdata::tzif(b'2', &v1, &v2, "CET-1")).unwrap();
+    assert_eq!(abolished.dst_abolished_at(), Some(Timespec::new(1050000000, 0)));
+
+    let observing = ZoneInfo::from_bytes(&testdata::tzif(b'2', &v1, &v2, "CET-1CEST,M3.5.0,M10.5.0/3")).unwrap();
+    assert_eq!(observing.dst_abolished_at(), None);
+
+    assert_eq!(ZoneInfo::from_posix_tz("UTC0").unwrap().dst_abolished_at(), None);
+}
+
+#[test]
+fn zoneinfo_at_exact_transition() {
+    let v1 = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "CEST")],
+                                  &[(1000000000, 1), (1020000000, 0)]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)).unwrap();
+    let at = Timespec::new(1000000000, 0);
+
+    assert_eq!(zi.get_actual_zoneinfo(at).unwrap().abbreviation, "CEST");
+    assert!(zi.get_actual_zoneinfo(Timespec::new(999999999, 0)).is_none());
+
+    let (next, info) = zi.get_next_transition_time(at).unwrap();
+    assert_eq!(next, Timespec::new(1020000000, 0));
+    assert_eq!(info.abbreviation, "CET");
+
+    let (next, _) = zi.get_next_transition_time(Timespec::new(999999999, 0)).unwrap();
+    assert_eq!(next, at);
+}
+
+#[test]
+fn zoneinfo_cached_offset() {
+    let v1 = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "CEST")],
+                                  &[(1000000000, 1), (1020000000, 0)]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)).unwrap();
+    let mut cached = zi.cached_offset();
+
+    assert_eq!(cached.offset_at(Timespec::new(999999999, 0)), None);
+    assert_eq!(cached.offset_at(Timespec::new(1000000000, 0)), Some(7200));
+    assert_eq!(cached.offset_at(Timespec::new(1019999999, 0)), Some(7200));
+    // crossing the transition
+    assert_eq!(cached.offset_at(Timespec::new(1020000000, 0)), Some(3600));
+    assert_eq!(cached.offset_at(Timespec::new(2000000000, 0)), Some(3600));
+    // the clock was set back
+    assert_eq!(cached.offset_at(Timespec::new(1010000000, 0)), Some(7200));
+}
+
+#[test]
+fn zoneinfo_zone_tree() {
+    let dir = std::env::temp_dir().join("zoneinfo_zone_tree");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("America/Indiana")).unwrap();
+    std::fs::create_dir_all(dir.join("Europe")).unwrap();
+    for zone in &["America/Indiana/Indianapolis", "America/Adak", "Europe/Amsterdam", "UTC"] {
+        std::fs::write(dir.join(zone), b"").unwrap();
+    }
+
+    let zone = |name: &str, location: &str| ZoneNode::Zone { name: name.to_string(), location: location.to_string() };
+    let directory = |name: &str, children| ZoneNode::Directory { name: name.to_string(), children };
+
+    assert_eq!(ZoneInfo::zone_tree(&dir), directory("zoneinfo_zone_tree", vec![
+        directory("America", vec![
+            zone("Adak", "America/Adak"),
+            directory("Indiana", vec![zone("Indianapolis", "America/Indiana/Indianapolis")]),
+        ]),
+        directory("Europe", vec![zone("Amsterdam", "Europe/Amsterdam")]),
+        zone("UTC", "UTC"),
+    ]));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn zoneinfo_resolve_local() {
+    let zi = ZoneInfo::by_tz("Europe/Amsterdam").unwrap();
+    let mar30 = civil::days_from_civil(2025, 3, 30) * 86400;
+    let oct26 = civil::days_from_civil(2025, 10, 26) * 86400;
+    let utc = |secs| Timespec::new(secs, 0);
+
+    // spring forward: 02:00 CET became 03:00 CEST at 01:00 UTC
+    assert_eq!(zi.resolve_local(2025, 3, 30, 1, 59, 59), LocalResult::Single(utc(mar30 + 3599)));
+    assert_eq!(zi.resolve_local(2025, 3, 30, 2, 0, 0), LocalResult::None);
+    assert_eq!(zi.resolve_local(2025, 3, 30, 2, 30, 0), LocalResult::None);
+    assert_eq!(zi.resolve_local(2025, 3, 30, 2, 59, 59), LocalResult::None);
+    assert_eq!(zi.resolve_local(2025, 3, 30, 3, 0, 0), LocalResult::Single(utc(mar30 + 3600)));
+
+    // fall back: 03:00 CEST became 02:00 CET at 01:00 UTC
+    assert_eq!(zi.resolve_local(2025, 10, 26, 1, 59, 59), LocalResult::Single(utc(oct26 - 1)));
+    assert_eq!(zi.resolve_local(2025, 10, 26, 2, 0, 0), LocalResult::Ambiguous(utc(oct26), utc(oct26 + 3600)));
+    assert_eq!(zi.resolve_local(2025, 10, 26, 2, 30, 0),
+               LocalResult::Ambiguous(utc(oct26 + 1800), utc(oct26 + 5400)));
+    assert_eq!(zi.resolve_local(2025, 10, 26, 2, 59, 59),
+               LocalResult::Ambiguous(utc(oct26 + 3599), utc(oct26 + 7199)));
+    assert_eq!(zi.resolve_local(2025, 10, 26, 3, 0, 0), LocalResult::Single(utc(oct26 + 7200)));
+
+    assert_eq!(zi.resolve_local(2025, 2, 29, 12, 0, 0), LocalResult::None);
+    assert_eq!(zi.resolve_local(2025, 1, 1, 24, 0, 0), LocalResult::None);
+}
+
+#[test]
+fn zoneinfo_diff_directories() {
+    let old = std::env::temp_dir().join("zoneinfo_diff_old");
+    let new = std::env::temp_dir().join("zoneinfo_diff_new");
+    for dir in &[&old, &new] {
+        let _ = std::fs::remove_dir_all(dir);
+        std::fs::create_dir_all(dir.join("Europe")).unwrap();
+    }
+
+    let cet = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "CEST")],
+                                   &[(1000000000, 1), (1020000000, 0)]);
+    let changed = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "CEST")],
+                                       &[(1000000000, 1), (1020003600, 0)]);
+    std::fs::write(old.join("Europe/Amsterdam"), testdata::tzif_v1(&cet)).unwrap();
+    std::fs::write(new.join("Europe/Amsterdam"), testdata::tzif_v1(&cet)).unwrap();
+    std::fs::write(old.join("Europe/Berlin"), testdata::tzif_v1(&cet)).unwrap();
+    std::fs::write(new.join("Europe/Berlin"), testdata::tzif_v1(&changed)).unwrap();
+    std::fs::write(old.join("Europe/Kyiv"), testdata::tzif_v1(&cet)).unwrap();
+    std::fs::write(new.join("Europe/Kiev"), testdata::tzif_v1(&cet)).unwrap();
+    std::fs::write(old.join("zone.tab"), b"# no zone").unwrap();
+    std::fs::write(new.join("zone.tab"), b"# no zone").unwrap();
+
+    assert_eq!(ZoneInfo::diff_directories(&old, &new), vec![
+        ZoneChange::Changed("Europe/Berlin".to_string()),
+        ZoneChange::Added("Europe/Kiev".to_string()),
+        ZoneChange::Removed("Europe/Kyiv".to_string()),
+        ZoneChange::Unparseable("zone.tab".to_string()),
+    ]);
+
+    for dir in &[&old, &new] {
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
+
+#[test]
+fn zoneinfo_subsecond_timestamps() {
+    let v1 = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "CEST")],
+                                  &[(1000000000, 1), (1020000000, 0)]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)).unwrap();
+
+    assert_eq!(zi.get_actual_zoneinfo(Timespec::new(1020000000, 1)).unwrap().abbreviation, "CET");
+    assert_eq!(zi.get_actual_zoneinfo(Timespec::new(1019999999, 999999999)).unwrap().abbreviation, "CEST");
+
+    let (next, _) = zi.get_next_transition_time(Timespec::new(1019999999, 999999999)).unwrap();
+    assert_eq!(next, Timespec::new(1020000000, 0));
+    assert!(zi.get_next_transition_time(Timespec::new(1020000000, 500)).is_none());
+}
+
+#[test]
+fn zoneinfo_merged_leap_seconds() {
+    let mut v1 = testdata::Block::new(&[(0, false, "UTC")], &[]);
+    let mut v2 = v1.clone();
+    v1.leaps = vec![(78796800, 1), (94694401, 2)];
+    v2.leaps = vec![(78796800, 1), (94694401, 2), (126230402, 3)];
+    let data = testdata::tzif(b'2', &v1, &v2, "UTC0");
+
+    let options = ParseOptions { parse_both_blocks: true, ..ParseOptions::default() };
+    let zi = ZoneInfo::from_bytes_with_options(&data, &options).unwrap();
+    let (first, second) = zi.leap_second_tables().unwrap();
+
+    assert_eq!(first.len(), 2);
+    assert_eq!(second.len(), 3);
+    assert_eq!(zi.merged_leap_seconds().len(), second.len());
+
+    let zi = ZoneInfo::from_bytes(&data).unwrap();
+    assert!(zi.leap_second_tables().is_none());
+    assert_eq!(zi.merged_leap_seconds().len(), zi.get_leap_second_transitions().len());
+}
+
+#[test]
+fn zoneinfo_wall_clock_jump_at() {
+    let zi = ZoneInfo::by_tz("Europe/Amsterdam").unwrap();
+    let mar30 = civil::days_from_civil(2025, 3, 30) * 86400;
+    let oct26 = civil::days_from_civil(2025, 10, 26) * 86400;
+
+    assert_eq!(zi.wall_clock_jump_at(Timespec::new(mar30 + 3600, 0)), Some(3600));
+    assert_eq!(zi.wall_clock_jump_at(Timespec::new(oct26 + 3600, 0)), Some(-3600));
+    assert_eq!(zi.wall_clock_jump_at(Timespec::new(oct26, 0)), None);
+
+    // computed from the POSIX TZ rule
+    let (spring, _) = zi.get_next_transition_time(Timespec::new(2500000000, 0)).unwrap();
+    assert_eq!(spring, Timespec::new(2500506000, 0));
+    assert_eq!(zi.wall_clock_jump_at(spring), Some(3600));
+    let (autumn, _) = zi.get_next_transition_time(spring).unwrap();
+    assert_eq!(zi.wall_clock_jump_at(autumn), Some(-3600));
+    assert_eq!(zi.wall_clock_jump_at(spring + Duration::hours(1)), None);
+}
+
+#[test]
+fn zoneinfo_without_transitions() {
+    let v1 = testdata::Block::new(&[(7200, true, "CEST"), (3600, false, "CET")], &[]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)).unwrap();
+
+    assert_eq!(zi.get_actual_zoneinfo(Timespec::new(0, 0)).unwrap().abbreviation, "CET");
+    assert_eq!(zi.tabulated_range(), None);
+
+    let empty = testdata::Block::new(&[], &[]);
+    let error = ZoneInfo::from_bytes(&testdata::tzif_v1(&empty)).err().unwrap();
+    assert_eq!(error.to_string(), "malformed zoneinfo data: no local time types");
+}
+
+#[test]
+fn zoneinfo_transitions_where() {
+    let v1 = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "CEST")],
+                                  &[(1000000000, 1), (1020000000, 0), (1030000000, 1)]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)).unwrap();
+
+    let dst: Vec<_> = zi.transitions_where(|info| info.isdst).map(|(time, _)| time.sec).collect();
+    assert_eq!(dst, vec![1000000000, 1030000000]);
+    assert_eq!(zi.transitions_where(|info| info.abbreviation == "CET").count(), 1);
+}
+
+#[test]
+fn zoneinfo_posix_numeric_abbreviation() {
+    let zi = ZoneInfo::by_tz("Asia/Kathmandu").unwrap();
+    let spec = zi.get_dst_specifier();
+    let now = Timespec::new(1700000000, 0);
+
+    assert_eq!(spec, "<+0545>-5:45");
+
+    let posix = ZoneInfo::from_posix_tz(&spec).unwrap();
+    let element = posix.get_actual_zoneinfo(now).unwrap();
+
+    assert_eq!(element.abbreviation, "+0545");
+    assert_eq!(element.abbreviation, zi.get_actual_zoneinfo(now).unwrap().abbreviation);
+    assert_eq!(element.ut_offset, 20700);
+}
+
+#[test]
+fn zoneinfo_pretransition_type() {
+    let v1 = testdata::Block::new(&[(1172, false, "LMT"), (3600, false, "CET"), (7200, true, "CEST")],
+                                  &[(1000000000, 2), (1020000000, 1)]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)).unwrap();
+
+    assert!(zi.get_actual_zoneinfo(Timespec::new(0, 0)).is_none());
+    assert_eq!(zi.pretransition_type().unwrap().abbreviation, "LMT");
+
+    let v1 = testdata::Block::new(&[(7200, true, "CEST"), (3600, false, "CET")],
+                                  &[(1000000000, 0), (1020000000, 1)]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)).unwrap();
+
+    assert_eq!(zi.pretransition_type().unwrap().abbreviation, "CET");
+}
+
+#[test]
+fn zoneinfo_roundtrip_local() {
+    let zi = ZoneInfo::by_tz("Europe/Amsterdam").unwrap();
+    let mar30 = civil::days_from_civil(2025, 3, 30) * 86400;
+    let oct26 = civil::days_from_civil(2025, 10, 26) * 86400;
+
+    for &local in &[mar30 + 7199, mar30 + 10800, oct26 + 5400, oct26 + 9000, oct26 + 10800] {
+        let local = Timespec::new(local, 0);
+        assert_eq!(zi.roundtrip_local(local), Some(local));
+    }
+
+    // skipped local times
+    assert_eq!(zi.roundtrip_local(Timespec::new(mar30 + 7200, 0)), None);
+    assert_eq!(zi.roundtrip_local(Timespec::new(mar30 + 9000, 0)), None);
+}
+
+#[test]
+fn zoneinfo_to_json() {
+    let v1 = testdata::Block::new(&[(0, false, "")], &[]);
+    let v2 = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "CEST")],
+                                  &[(1000000000, 1), (1020000000, 0)]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif(b'2', &v1, &v2, "CET-1CEST,M3.5.0,M10.5.0/3")).unwrap();
+
+    assert_eq!(zi.to_json(), concat!(
+        r#"{"name":null,"version":"2","transitions":["#,
+        r#"{"time":1000000000,"offset":7200,"isdst":true,"abbreviation":"CEST"},"#,
+        r#"{"time":1020000000,"offset":3600,"isdst":false,"abbreviation":"CET"}"#,
+        r#"],"posix_tz":"CET-1CEST,M3.5.0,M10.5.0/3"}"#));
+
+    assert_eq!(json_string("a\"b\\c\u{1}"), r#""a\"b\\c\u0001""#);
+
+    let zi = ZoneInfo::by_tz("Europe/Amsterdam").unwrap();
+    assert!(zi.to_json().starts_with(r#"{"name":"Europe/Amsterdam","#));
+}
+
+#[test]
+fn zoneinfo_transitions_on_local_date() {
+    let zi = ZoneInfo::by_tz("Europe/Amsterdam").unwrap();
+    let mar30 = civil::days_from_civil(2025, 3, 30) * 86400;
+
+    // spring forward: 02:00 CET became 03:00 CEST at 01:00 UTC
+    let transitions = zi.transitions_on_local_date(2025, 3, 30);
+    assert_eq!(transitions.len(), 1);
+    assert_eq!(transitions[0].0, Timespec::new(mar30 + 3600, 0));
+    assert_eq!(transitions[0].1.abbreviation, "CEST");
+
+    assert!(zi.transitions_on_local_date(2025, 3, 29).is_empty());
+    assert!(zi.transitions_on_local_date(2025, 3, 31).is_empty());
+    assert!(zi.transitions_on_local_date(2025, 2, 30).is_empty());
+
+    // computed from the POSIX TZ rule
+    let mar27 = civil::days_from_civil(2050, 3, 27) * 86400;
+    let transitions = zi.transitions_on_local_date(2050, 3, 27);
+    assert_eq!(transitions.len(), 1);
+    assert_eq!(transitions[0].0, Timespec::new(mar27 + 3600, 0));
+    assert_eq!(transitions[0].1.abbreviation, "CEST");
+    assert!(zi.transitions_on_local_date(2050, 3, 26).is_empty());
+    assert_eq!(zi.transitions_on_local_date(2050, 10, 30).len(), 1);
+
+    // a clock change at local midnight belongs to the day it starts
+    let v1 = testdata::Block::new(&[(0, false, "STD"), (-3600, true, "DST")], &[(86400, 1)]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)).unwrap();
+    assert_eq!(zi.transitions_on_local_date(1970, 1, 2).len(), 1);
+    assert!(zi.transitions_on_local_date(1970, 1, 1).is_empty());
+}
+
+#[test]
+fn zoneinfo_element_at_extrapolated() {
+    let v1 = testdata::Block::new(&[(0, false, "")], &[]);
+    let v2 = testdata::Block::new(&[(0, false, "LMT"), (3600, false, "OLD")], &[(1000000000, 1)]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif(b'2', &v1, &v2, "NEW-1")).unwrap();
+
+    assert_eq!(zi.element_at_extrapolated(Timespec::new(1000000000, 0)).unwrap().abbreviation, "OLD");
+    let element = zi.element_at_extrapolated(Timespec::new(1000000001, 0)).unwrap();
+    assert_eq!(element.abbreviation, "NEW");
+    assert_eq!(element.ut_offset, 3600);
+    assert!(zi.element_at_extrapolated(Timespec::new(0, 0)).is_none());
+
+    let zi = ZoneInfo::by_tz("America/New_York").unwrap();
+    let summer = Timespec::new(civil::days_from_civil(2090, 7, 1) * 86400, 0);
+    let winter = Timespec::new(civil::days_from_civil(2090, 1, 1) * 86400, 0);
+    let element = zi.element_at_extrapolated(summer).unwrap();
+
+    assert_eq!(zi.current_date_source(summer), DateSource::PosixRule);
+    assert_eq!(element.abbreviation, "EDT");
+    assert_eq!(element.ut_offset, -14400);
+    assert!(element.isdst);
+    assert_eq!(zi.element_at_extrapolated(winter).unwrap().abbreviation, "EST");
+}
+
+#[test]
+fn zoneinfo_offset_distribution() {
+    let distribution = ZoneInfo::offset_distribution(Timespec::new(1700000000, 0));
+
+    assert!(distribution[&0].iter().any(|name| name == "Etc/UTC"));
+    assert!(distribution[&20700].iter().any(|name| name == "Asia/Kathmandu"));
+    assert!(distribution[&19800].iter().any(|name| name == "Asia/Kolkata"));
+    assert!(!distribution.values().any(|names| names.iter().any(|name| name == "zone.tab")));
+}
+
+#[test]
+fn zoneinfo_zone_source() {
+    struct MemorySource(BTreeMap<String, Vec<u8>>);
+
+    impl ZoneSource for MemorySource {
+        fn read(&self, name: &str) -> std::io::Result<Vec<u8>> {
+            self.0.get(name).cloned().ok_or_else(|| std::io::Error::from(std::io::ErrorKind::NotFound))
+        }
+
+        fn list(&self) -> std::io::Result<Vec<String>> {
+            Ok(self.0.keys().cloned().collect())
+        }
+    }
+
+    let v1 = testdata::Block::new(&[(3600, false, "CET")], &[(0, 0)]);
+    let mut files = BTreeMap::new();
+    files.insert("Virtual/Zone".to_string(), testdata::tzif_v1(&v1));
+    files.insert("Broken".to_string(), b"TZif".to_vec());
+    let source = MemorySource(files);
+
+    assert_eq!(ZoneInfo::get_tz_locations_from(&source), vec!["Broken", "Virtual/Zone"]);
+    let zi = ZoneInfo::by_tz_from(&source, "Virtual/Zone").unwrap();
+    assert_eq!(zi.name(), Some("Virtual/Zone"));
+    assert_eq!(zi.offset_at_epoch(), Some(3600));
+    assert!(ZoneInfo::by_tz_from(&source, "Broken").is_err());
+    match ZoneInfo::by_tz_from(&source, "Missing") {
+        Err(ZoneInfoError::Io(err)) => assert_eq!(err.kind(), std::io::ErrorKind::NotFound),
+        _ => panic!("missing location not reported")
+    }
+
+    let fs = FsZoneSource::new(Path::new("/usr/share/zoneinfo"));
+    assert!(ZoneInfo::get_tz_locations_from(&fs).contains(&"Europe/Amsterdam".to_string()));
+    assert_eq!(ZoneInfo::by_tz_from(&fs, "Europe/Amsterdam").unwrap().fingerprint(),
+               ZoneInfo::by_tz("Europe/Amsterdam").unwrap().fingerprint());
+}
+
+#[test]
+fn zoneinfo_upcoming_transitions() {
+    let v1 = testdata::Block::new(&[(0, false, "")], &[]);
+    let v2 = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "CEST"), (3600, false, "CET")],
+                                  &[(990000000, 0), (1000000000, 1), (1010000000, 2), (1020000000, 0)]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif(b'2', &v1, &v2, "CET-1CEST,M3.5.0,M10.5.0/3")).unwrap();
+    let mar30 = civil::days_from_civil(2003, 3, 30) * 86400;
+    let oct26 = civil::days_from_civil(2003, 10, 26) * 86400;
+
+    // the listed switch between equal types at 1020000000 and the computed
+    // switch to CET of October 2002 don't change anything
+    let upcoming: Vec<(i64, String)> = zi.upcoming_transitions(Timespec::new(1000000000, 0), 4)
+                                         .into_iter()
+                                         .map(|(time, info)| (time.sec, info.abbreviation))
+                                         .collect();
+    assert_eq!(upcoming, vec![(1000000000, "CEST".to_string()), (1010000000, "CET".to_string()),
+                              (mar30 + 3600, "CEST".to_string()), (oct26 + 3600, "CET".to_string())]);
+
+    assert_eq!(zi.upcoming_transitions(Timespec::new(1000000001, 0), 1)[0].0, Timespec::new(1010000000, 0));
+    assert!(zi.upcoming_transitions(Timespec::new(0, 0), 0).is_empty());
+    assert_eq!(zi.upcoming_transitions(Timespec::new(mar30 + 3601, 0), 100).len(), 100);
+    let all = zi.upcoming_transitions(Timespec::new(1000000000, 0), usize::MAX);
+    assert_eq!(all.len(), 2 + 2 * 10000);
+    assert_eq!(all[2].0, Timespec::new(mar30 + 3600, 0));
+
+    let zi = ZoneInfo::by_tz("Asia/Kathmandu").unwrap();
+    assert!(zi.upcoming_transitions(Timespec::new(1700000000, 0), 5).is_empty());
+}
+
+#[test]
+fn zoneinfo_typical_transition_local_hour() {
+    assert_eq!(ZoneInfo::by_tz("America/New_York").unwrap().typical_transition_local_hour(), Some(2));
+    // EU zones switch at 01:00 UTC, so at 02:00 and 03:00 local time in CET
+    assert_eq!(ZoneInfo::by_tz("Europe/London").unwrap().typical_transition_local_hour(), Some(1));
+    assert_eq!(ZoneInfo::by_tz("Europe/Amsterdam").unwrap().typical_transition_local_hour(), Some(2));
+    assert_eq!(ZoneInfo::by_tz("Asia/Kathmandu").unwrap().typical_transition_local_hour(), None);
+    assert_eq!(ZoneInfo::by_tz("Asia/Tokyo").unwrap().typical_transition_local_hour(), None);
+}
+
+#[test]
+fn zoneinfo_tzdata_version() {
+    let dir = std::env::temp_dir().join("zoneinfo_tzdata_version");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    assert_eq!(ZoneInfo::tzdata_version(&dir), None);
+    std::fs::write(dir.join("tzdata.zi"), b"# version 2024a\n# This zic input file is in the public domain.\n").unwrap();
+    assert_eq!(ZoneInfo::tzdata_version(&dir), Some("2024a".to_string()));
+    std::fs::write(dir.join("+VERSION"), b"2024b\n").unwrap();
+    assert_eq!(ZoneInfo::tzdata_version(&dir), Some("2024b".to_string()));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn zoneinfo_utc_to_tai() {
+    let mut v1 = testdata::Block::new(&[(0, false, "UTC")], &[]);
+    v1.leaps = vec![(78796800, 1), (94694401, 2), (126230402, 3)];
+    let zi = ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)).unwrap();
+
+    assert_eq!(zi.leap_seconds_at(Timespec::new(0, 0)), 0);
+    assert_eq!(zi.leap_seconds_at(Timespec::new(94694400, 999999999)), 1);
+    assert_eq!(zi.leap_seconds_at(Timespec::new(94694401, 0)), 2);
+    assert_eq!(zi.leap_seconds_at(Timespec::new(2000000000, 0)), 3);
+    assert_eq!(zi.utc_to_tai(Timespec::new(94694401, 5)), Some(Timespec::new(94694413, 5)));
+
+    // without leap second table the offset to TAI is unknown
+    let zi = ZoneInfo::by_tz("Europe/Amsterdam").unwrap();
+    assert_eq!(zi.utc_to_tai(Timespec::new(2000000000, 0)), None);
+}
+
+#[test]
+fn zoneinfo_current_state_repr() {
+    let v1 = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "LONGNAME")], &[(0, 0), (1000, 1)]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)).unwrap();
+
+    assert_eq!(zi.current_state_repr(Timespec::new(0, 0)),
+               Some(CurrentState { offset: 3600, isdst: false, abbr: *b"CET\0\0\0\0\0" }));
+    assert_eq!(zi.current_state_repr(Timespec::new(1000, 0)),
+               Some(CurrentState { offset: 7200, isdst: true, abbr: *b"LONGNAM\0" }));
+    assert_eq!(zi.current_state_repr(Timespec::new(-1, 0)), None);
+}
+
+#[test]
+fn zoneinfo_transitions_within() {
+    let v1 = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "CEST"), (3600, false, "CET")],
+                                  &[(0, 0), (1000, 1), (2000, 2), (3000, 0), (4000, 1)]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)).unwrap();
+    let times = |now, window| -> Vec<i64> {
+        zi.transitions_around(Timespec::new(now, 0), Duration::seconds(window))
+          .into_iter()
+          .map(|(time, _)| time.sec)
+          .collect()
+    };
+
+    // the switch between both CET types at 3000 doesn't change anything
+    assert_eq!(times(2500, 1600), vec![1000, 2000, 4000]);
+    assert_eq!(times(2500, 1500), vec![1000, 2000]);
+    assert_eq!(times(2500, 500), vec![2000]);
+    assert_eq!(times(3000, 10), Vec::<i64>::new());
+    assert_eq!(times(0, 10), vec![0]);
+
+    // beyond the table, like slim zoneinfo files without listed transitions
+    let v1 = testdata::Block::new(&[(0, false, "")], &[]);
+    let v2 = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "CEST")], &[(1000000000, 0)]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif(b'2', &v1, &v2, "CET-1CEST,M3.5.0,M10.5.0/3")).unwrap();
+    let mar27 = civil::days_from_civil(2050, 3, 27) * 86400;
+    let transitions = zi.transitions_around(Timespec::new(mar27, 0), Duration::days(1));
+    assert_eq!(transitions.len(), 1);
+    assert_eq!(transitions[0].0, Timespec::new(mar27 + 3600, 0));
+    assert_eq!(transitions[0].1.abbreviation, "CEST");
+    assert_eq!(zi.transitions_around(Timespec::new(mar27, 0), Duration::days(230)).len(), 3);
+
+    let zi = ZoneInfo::by_tz("Asia/Kathmandu").unwrap();
+    assert!(zi.transitions_within(Duration::days(365)).is_empty());
+}
+
+#[test]
+fn zoneinfo_latest_transition() {
+    let v1 = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "CEST")],
+                                  &[(1000000000, 1), (1020000000, 0)]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)).unwrap();
+
+    assert_eq!(zi.latest_transition(), Some(Timespec::new(1020000000, 0)));
+    assert!(zi.get_dst_specifier().is_empty());
+
+    let v1 = testdata::Block::new(&[(0, false, "UTC")], &[]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)).unwrap();
+
+    assert_eq!(zi.latest_transition(), None);
+}
+
+#[test]
+fn zoneinfo_tm_local_to_utc() {
+    let zi = ZoneInfo::by_tz("Europe/Amsterdam").unwrap();
+    let mar30 = civil::days_from_civil(2025, 3, 30) * 86400;
+    let oct26 = civil::days_from_civil(2025, 10, 26) * 86400;
+    let tm = |mon, mday, hour, min| time::Tm {
+        tm_sec: 0, tm_min: min, tm_hour: hour, tm_mday: mday, tm_mon: mon, tm_year: 125,
+        tm_wday: 0, tm_yday: 0, tm_isdst: 0, tm_utcoff: -36000, tm_nsec: 500,
+    };
+
+    assert_eq!(zi.tm_local_to_utc(&tm(2, 30, 1, 30)), LocalResult::Single(Timespec::new(mar30 + 1800, 500)));
+    assert_eq!(zi.tm_local_to_utc(&tm(2, 30, 2, 30)), LocalResult::None);
+    assert_eq!(zi.tm_local_to_utc(&tm(2, 30, 3, 30)), LocalResult::Single(Timespec::new(mar30 + 5400, 500)));
+    assert_eq!(zi.tm_local_to_utc(&tm(9, 26, 2, 30)),
+               LocalResult::Ambiguous(Timespec::new(oct26 + 1800, 500), Timespec::new(oct26 + 5400, 500)));
+    assert_eq!(zi.tm_local_to_utc(&tm(12, 1, 0, 0)), LocalResult::None);
+    assert_eq!(zi.tm_local_to_utc(&tm(0, 1, -1, 0)), LocalResult::None);
+}
+
+#[test]
+fn zoneinfo_offset_signature() {
+    let v1 = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "CEST"), (3600, false, "MET")],
+                                  &[(0, 2), (1000, 1), (2000, 0), (3000, 1)]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)).unwrap();
+
+    assert_eq!(zi.offset_signature(), vec![3600, 7200]);
+    assert_eq!(ZoneInfo::from_posix_tz("CET-1CEST,M3.5.0,M10.5.0/3").unwrap().offset_signature(),
+               vec![3600, 7200]);
+    assert_eq!(ZoneInfo::by_tz("Europe/Amsterdam").unwrap().offset_signature(),
+               vec![1172, 4772, 4800, 1200, 7200, 3600]);
+}
+
+#[test]
+fn zoneinfo_timestamp_kinds() {
+    let zi = ZoneInfo::by_tz("Europe/Amsterdam").unwrap();
+    let moment = Timespec::new(1700000000, 0);
+
+    assert_eq!(zi.get_actual_zoneinfo(UtcTimestamp(moment)).unwrap().abbreviation, "CET");
+    assert_eq!(zi.get_actual_zoneinfo(moment).unwrap().abbreviation, "CET");
+    assert_eq!(zi.local_to_utc(LocalTimestamp(Timespec::new(1700003600, 0))), LocalResult::Single(moment));
+    assert_eq!(zi.local_to_utc(Timespec::new(1700003600, 0)), LocalResult::Single(moment));
+}
+
+#[test]
+fn zoneinfo_dst_observing_zones() {
+    assert!(ZoneInfo::by_tz("Asia/Tokyo").unwrap().observes_dst());
+    assert!(!ZoneInfo::by_tz("Asia/Kathmandu").unwrap().observes_dst());
+    assert!(ZoneInfo::from_posix_tz("CET-1CEST,M3.5.0,M10.5.0/3").unwrap().observes_dst());
+
+    let zones = ZoneInfo::dst_observing_zones();
+    assert!(zones.contains(&"Europe/Amsterdam".to_string()));
+    assert!(zones.contains(&"America/New_York".to_string()));
+    assert!(!zones.contains(&"Asia/Tokyo".to_string()));
+    assert!(!zones.contains(&"Asia/Kathmandu".to_string()));
+}
+
+#[test]
+fn zoneinfo_parse_header_only() {
+    let mut v2 = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "CEST")],
+                                      &[(1000000000, 1), (1020000000, 0)]);
+    v2.leaps = vec![(78796800, 1)];
+    let v1 = testdata::Block::new(&[(0, false, "")], &[]);
+
+    assert_eq!(ZoneInfo::parse_header_only(&testdata::tzif(b'3', &v1, &v2, "CET-1")).unwrap(), ZoneHeader {
+        version: b'3', isut_count: 0, isstd_count: 0, leap_count: 1, transition_count: 2, type_count: 2,
+        char_count: 9
+    });
+    assert_eq!(ZoneInfo::parse_header_only(&testdata::tzif_v1(&v1)).unwrap().version, 0);
+
+    // the tables themselves are not read
+    let data = testdata::tzif_v1(&v2);
+    assert_eq!(ZoneInfo::parse_header_only(&data[..44]).unwrap().transition_count, 2);
+    assert!(ZoneInfo::parse_header_only(&data[..43]).is_err());
+}
+
+#[test]
+fn zoneinfo_header_counts_overflow() {
+    let block = testdata::Block::new(&[(3600, false, "CET")], &[]);
+    let mut data = testdata::tzif(b'2', &block, &block, "CET-1");
+    // both indicator counts of the 32-bit header set to 0xffffffff
+    for byte in &mut data[20..28] {
+        *byte = 0xff;
+    }
+
+    assert!(ZoneInfo::parse_header_only(&data).is_err());
+    assert!(ZoneInfo::from_bytes(&data).is_err());
+    assert!(ZoneInfo::from_bytes_with_options(&data, &ParseOptions { parse_both_blocks: true,
+                                                                  ..ParseOptions::default() })
+        .is_err());
+}
+
+#[test]
+fn zoneinfo_first_use_of_offset() {
+    let zi = ZoneInfo::by_tz("Europe/Amsterdam").unwrap();
+    let may16 = civil::days_from_civil(1940, 5, 16) * 86400;
+
+    // the German occupation switched from +0020 to CEST at 00:00 on May 16th
+    // 1940, and CET followed in the winter
+    assert_eq!(zi.first_use_of_offset(7200), Some(Timespec::new(may16 - 1200, 0)));
+    assert!(zi.first_use_of_offset(3600).unwrap() > Timespec::new(may16, 0));
+    assert_eq!(zi.first_use_of_offset(-3600), None);
+
+    let utc = ZoneInfo::from_bytes(&testdata::tzif_v1(&testdata::Block::new(&[(0, false, "UTC")], &[]))).unwrap();
+    assert_eq!(utc.first_use_of_offset(0), Some(Timespec::new(i64::MIN, 0)));
+}
+
+#[test]
+fn zoneinfo_from_posix_tz_only() {
+    let utc = |year, month, day, hour: i64| {
+        Timespec::new(civil::days_from_civil(year, month, day) * 86400 + hour * 3600, 0)
+    };
+    let times = |zi: &ZoneInfo| -> Vec<Timespec> {
+        zi.transition_table().iter().map(|t| Timespec::new(t.0, 0)).collect()
+    };
+
+    let eu = ZoneInfo::from_posix_tz_only("CET-1CEST,M3.5.0,M10.5.0/3", 2024..2026).unwrap();
+    assert_eq!(times(&eu), vec![utc(2024, 3, 31, 1), utc(2024, 10, 27, 1), utc(2025, 3, 30, 1), utc(2025, 10, 26, 1)]);
+
+    let us = ZoneInfo::from_posix_tz_only("EST5EDT,M3.2.0,M11.1.0", 2024..2025).unwrap();
+    assert_eq!(times(&us), vec![utc(2024, 3, 10, 7), utc(2024, 11, 3, 6)]);
+    assert_eq!(us.get_actual_zoneinfo(utc(2024, 7, 1, 0)).unwrap().abbreviation, "EDT");
+
+    let err = ZoneInfo::from_posix_tz_only("CET-1CEST,M13.5.0,M10.5.0/3", 2024..2025).err().unwrap();
+    assert!(err.to_string().starts_with("invalid POSIX TZ string"));
+    assert!(ZoneInfo::from_posix_tz_only("CET-1", 2025..2025).is_err());
+}
+
+#[test]
+fn zoneinfo_posix_rule_at_extreme_timestamps() {
+    let v1 = testdata::Block::new(&[(0, false, "")], &[]);
+    let v2 = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "CEST")], &[(1000000000, 0)]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif(b'2', &v1, &v2, "CET-1CEST,M3.5.0,M10.5.0/3")).unwrap();
+    let year = 366 * 86400;
+
+    assert!(zi.get_actual_zoneinfo(Timespec::new(i64::MAX, 0)).is_some());
+    assert!(zi.is_dst_at(Timespec::new(i64::MAX, 0)).is_some());
+
+    let (previous, _) = zi.get_previous_transition_time(Timespec::new(i64::MAX, 0)).unwrap();
+    assert!(previous.sec > i64::MAX - year);
+    let (next, _) = zi.get_next_transition_time(Timespec::new(1 << 60, 0)).unwrap();
+    assert!(next.sec > 1 << 60 && next.sec < (1 << 60) + year);
+    assert!(zi.get_previous_transition_time(Timespec::new(1 << 60, 0)).unwrap().0.sec > (1 << 60) - year);
+}
+
+#[test]
+fn zoneinfo_is_standard_time_at() {
+    let january = Timespec::new(civil::days_from_civil(2024, 1, 15) * 86400, 0);
+    let july = Timespec::new(civil::days_from_civil(2024, 7, 15) * 86400, 0);
+
+    let zi = ZoneInfo::by_tz("Europe/Amsterdam").unwrap();
+    assert_eq!(zi.is_standard_time_at(january), Some(true));
+    assert_eq!(zi.is_standard_time_at(july), Some(false));
+
+    // tzdata flags the winter time (GMT) of Dublin as daylight saving time
+    let zi = ZoneInfo::by_tz("Europe/Dublin").unwrap();
+    assert_eq!(zi.get_abbreviation_at(january), Some("GMT"));
+    assert_eq!(zi.is_standard_time_at(january), Some(false));
+    assert_eq!(zi.get_abbreviation_at(july), Some("IST"));
+    assert_eq!(zi.is_standard_time_at(july), Some(true));
+
+    let v1 = testdata::Block::new(&[(3600, false, "CET")], &[(0, 0)]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)).unwrap();
+    assert_eq!(zi.is_standard_time_at(Timespec::new(-1, 0)), None);
+}
+
+#[test]
+fn zoneinfo_transition_utc_and_local() {
+    let v1 = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "CEST"), (-3600, false, "W")],
+                                  &[(1000000000, 1), (1020000000, 0), (i64::MAX - 1, 2), (i64::MAX, 1)]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif(b'2', &testdata::Block::new(&[(0, false, "")], &[]), &v1, ""))
+        .unwrap();
+    let table: Vec<(i64, i64, String)> = zi.transition_utc_and_local()
+                                           .into_iter()
+                                           .map(|(utc, local, info)| (utc.sec, local.sec, info.abbreviation))
+                                           .collect();
+
+    assert_eq!(table, vec![(1000000000, 1000007200, "CEST".to_string()),
+                           (1020000000, 1020003600, "CET".to_string()),
+                           (i64::MAX - 1, i64::MAX - 3601, "W".to_string()),
+                           (i64::MAX, i64::MAX, "CEST".to_string())]);
+
+    // the initial sentinel of zones without transitions is left out
+    let utc = ZoneInfo::from_bytes(&testdata::tzif_v1(&testdata::Block::new(&[(0, false, "UTC")], &[]))).unwrap();
+    assert_eq!(utc.get_transitions().len(), 1);
+    assert!(utc.transition_utc_and_local().is_empty());
+}
+
+#[test]
+fn zoneinfo_offset_at_epoch() {
+    // the table ends before 1970, the southern hemisphere rule has daylight
+    // saving time in January
+    let v1 = testdata::Block::new(&[(0, false, "")], &[]);
+    let v2 = testdata::Block::new(&[(36000, false, "AEST")], &[(-100000000, 0)]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif(b'2', &v1, &v2, "AEST-10AEDT,M10.1.0,M4.1.0/3")).unwrap();
+
+    assert_eq!(zi.offset_at_epoch(), Some(39600));
+    assert_eq!(zi.offset_at_epoch(), zi.get_offset_at(Timespec::new(0, 0)));
+}
+
+#[test]
+fn zoneinfo_offset_timeline() {
+    let v1 = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "CEST")],
+                                  &[(1000000000, 1), (1020000000, 0)]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)).unwrap();
+
+    assert_eq!(zi.offset_timeline(), vec![(Timespec::new(1000000000, 0), 7200), (Timespec::new(1020000000, 0), 3600)]);
+    assert_eq!(zi.pretransition_type().unwrap().ut_offset, 3600);
+
+    let utc = ZoneInfo::from_bytes(&testdata::tzif_v1(&testdata::Block::new(&[(0, false, "UTC")], &[]))).unwrap();
+    assert!(utc.offset_timeline().is_empty());
+}
+
+#[test]
+fn zoneinfo_strict_tail() {
+    let v1 = testdata::Block::new(&[(0, false, "")], &[]);
+    let v2 = testdata::Block::new(&[(3600, false, "CET")], &[]);
+    let data = testdata::tzif(b'2', &v1, &v2, "CET-1");
+
+    assert_eq!(ZoneInfo::from_bytes(&data).unwrap().get_dst_specifier(), "CET-1");
+
+    let mut trailing = data.clone();
+    trailing.extend(b"junk");
+    let mut unterminated = data.clone();
+    unterminated.pop();
+    let mut unprefixed = data[..data.len() - 7].to_vec();
+    unprefixed.extend(b"CET-1\n\n");
+    let mut not_utf8 = data[..data.len() - 6].to_vec();
+    not_utf8.extend(b"\xffET-1\n");
+
+    for (data, message) in &[(trailing, "trailing data"), (unterminated, "not followed"),
+                             (unprefixed, "not preceded"), (not_utf8, "UTF-8"),
+                             (data[..data.len() - 7].to_vec(), "not preceded")] {
+        let err = ZoneInfo::from_bytes(data).err().unwrap();
+        assert!(err.to_string().contains(message), "{}", err);
+    }
+}
+
+#[test]
+fn zoneinfo_snapshot_offset_around() {
+    let v1 = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "CEST")],
+                                  &[(1000000000, 1), (1020000000, 0)]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)).unwrap();
+    let snapshot = zi.snapshot_offset_around(Timespec::new(1010000000, 0));
+
+    assert_eq!(snapshot, OffsetSnapshot {
+        offset: 7200, start: Timespec::new(1000000000, 0), end: Some(Timespec::new(1020000000, 0))
+    });
+    assert!(snapshot.contains(Timespec::new(1000000000, 0)));
+    assert!(!snapshot.contains(Timespec::new(1020000000, 0)));
+    assert_eq!(snapshot.to_local(Timespec::new(1019999999, 5)), Some(Timespec::new(1020007199, 5)));
+    assert_eq!(snapshot.to_local(Timespec::new(999999999, 0)), None);
+
+    assert_eq!(zi.snapshot_offset_around(Timespec::new(0, 0)), OffsetSnapshot {
+        offset: 3600, start: Timespec::new(i64::MIN, 0), end: Some(Timespec::new(1000000000, 0))
+    });
+    assert_eq!(zi.snapshot_offset_around(Timespec::new(2000000000, 0)).end, None);
+}
+
+#[test]
+fn zoneinfo_is_southern_hemisphere_dst() {
+    assert_eq!(ZoneInfo::by_tz("Australia/Sydney").unwrap().is_southern_hemisphere_dst(), Some(true));
+    assert_eq!(ZoneInfo::by_tz("Europe/Berlin").unwrap().is_southern_hemisphere_dst(), Some(false));
+    assert_eq!(ZoneInfo::by_tz("Asia/Kathmandu").unwrap().is_southern_hemisphere_dst(), None);
+    assert_eq!(ZoneInfo::from_posix_tz("XXX3YYY,J300,J60").unwrap().is_southern_hemisphere_dst(), Some(true));
+    assert_eq!(ZoneInfo::from_posix_tz("XXX3YYY,59,300").unwrap().is_southern_hemisphere_dst(), Some(false));
+}
+
+#[test]
+fn zoneinfo_active_offsets_at() {
+    let offsets = ZoneInfo::active_offsets_at(Timespec::new(1700000000, 0));
+
+    // Pacific/Chatham observes UTC+13:45 during its summer
+    assert!(offsets.contains(&49500));
+    assert!(offsets.contains(&20700));
+    assert!(offsets.contains(&0));
+    assert!(offsets.windows(2).all(|w| w[0] < w[1]));
+}
+
+#[test]
+fn zoneinfo_error_variants() {
+    use std::error::Error;
+
+    let v1 = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "CEST")],
+                                  &[(1000000000, 1), (1020000000, 0)]);
+    let data = testdata::tzif_v1(&v1);
+
+    assert!(matches!(ZoneInfo::from_bytes(&data[..data.len() - 1]), Err(ZoneInfoError::Truncated)));
+    assert!(matches!(ZoneInfo::from_bytes(&data[..10]), Err(ZoneInfoError::Truncated)));
+
+    let mut version = data.clone();
+    version[4] = b'1';
+    let error = ZoneInfo::from_bytes(&version).err().unwrap();
+    assert!(matches!(error, ZoneInfoError::UnsupportedVersion('1')));
+    assert_eq!(error.to_string(), "unsupported zoneinfo version '1'");
+
+    let bad = testdata::Block::new(&[(3600, false, "C\u{e9}T")], &[]);
+    let mut bad_utf8 = testdata::tzif_v1(&bad);
+    let len = bad_utf8.len();
+    bad_utf8[len - 3] = 0xff;
+    let error = ZoneInfo::from_bytes(&bad_utf8).err().unwrap();
+    assert!(matches!(error, ZoneInfoError::BadUtf8(_)));
+    assert!(error.source().is_some());
+
+    let error = ZoneInfo::new(Path::new("/nonexistent/zoneinfo")).err().unwrap();
+    assert!(matches!(error, ZoneInfoError::Io(ref err) if err.kind() == std::io::ErrorKind::NotFound));
+}
+
+#[test]
+fn zoneinfo_posix_tail_mismatches() {
+    assert!(ZoneInfo::by_tz("Europe/Amsterdam").unwrap().posix_tail_consistent(1996..2038));
+    assert!(ZoneInfo::by_tz("America/New_York").unwrap().posix_tail_consistent(2007..2038));
+    assert!(!ZoneInfo::by_tz("America/New_York").unwrap().posix_tail_consistent(2006..2007));
+
+    let mar31 = civil::days_from_civil(2024, 3, 31) * 86400 + 3600;
+    let oct27 = civil::days_from_civil(2024, 10, 27) * 86400 + 3600;
+    let v1 = testdata::Block::new(&[(0, false, "")], &[]);
+    let v2 = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "CEST")],
+                                  &[(mar31, 1), (oct27 + 3600, 0)]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif(b'2', &v1, &v2, "CET-1CEST,M3.5.0,M10.5.0/3")).unwrap();
+    let mismatches = zi.posix_tail_mismatches(2024..2025);
+
+    assert_eq!(mismatches.len(), 2);
+    assert_eq!(mismatches[0].time, Timespec::new(oct27, 0));
+    assert!(mismatches[0].table.is_none());
+    assert_eq!(mismatches[0].posix.as_ref().unwrap().abbreviation, "CET");
+    assert_eq!(mismatches[1].time, Timespec::new(oct27 + 3600, 0));
+    assert!(mismatches[1].posix.is_none());
+}
+
+#[test]
+fn zoneinfo_invalid_magic() {
+    // deterministic "random" bytes, including invalid UTF-8
+    let mut state = 12345u32;
+    let random: Vec<u8> = (0..4096).map(|_| {
+        state = state.wrapping_mul(1103515245).wrapping_add(12345);
+        (state >> 16) as u8
+    }).collect();
+
+    assert!(matches!(ZoneInfo::from_bytes(&random), Err(ZoneInfoError::InvalidMagic)));
+    assert!(matches!(ZoneInfo::parse_header_only(&random), Err(ZoneInfoError::InvalidMagic)));
+    assert!(matches!(ZoneInfo::new(Path::new("/usr/share/zoneinfo/zone.tab")), Err(ZoneInfoError::InvalidMagic)));
+    assert!(matches!(ZoneInfo::from_bytes(b"TZi"), Err(ZoneInfoError::Truncated)));
+}
+
+#[test]
+fn zoneinfo_format_at() {
+    let zi = ZoneInfo::by_tz("Europe/Amsterdam").unwrap();
+    let summer = Timespec::new(civil::days_from_civil(2025, 7, 1) * 86400, 0);
+
+    assert_eq!(zi.format_at(summer, "%Z (%o) %z 100%% %q%").unwrap(), "CEST (+02:00) +0200 100% %q%");
+    assert_eq!(zi.format_at(Timespec::new(-2000000000, 0), "%z %o").unwrap(), "+001932 +00:19:32");
+
+    let zi = ZoneInfo::by_tz("America/St_Johns").unwrap();
+    assert_eq!(zi.format_at(Timespec::new(1700000000, 0), "%Z %o").unwrap(), "NST -03:30");
+
+    let v1 = testdata::Block::new(&[(3600, false, "CET")], &[(0, 0)]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)).unwrap();
+    assert_eq!(zi.format_at(Timespec::new(-1, 0), "%Z"), None);
+}
+
+#[test]
+fn zoneinfo_lossy_abbreviations() {
+    let mut v1 = testdata::Block::new(&[(3600, false, "CET")], &[]);
+    v1.chars = b"C\xffT\0".to_vec();
+    let data = testdata::tzif_v1(&v1);
+
+    assert!(matches!(ZoneInfo::from_bytes(&data), Err(ZoneInfoError::BadUtf8(_))));
+
+    let options = ParseOptions { lossy_abbreviations: true, ..ParseOptions::default() };
+    let zi = ZoneInfo::from_bytes_with_options(&data, &options).unwrap();
+    assert_eq!(zi.get_actual_zoneinfo(Timespec::new(0, 0)).unwrap().abbreviation, "C\u{fffd}T");
+}
 
-    assert_eq!(zi.get_dst_specifier(), zic.get_dst_specifier());
+#[test]
+fn zoneinfo_abbreviation_index_out_of_range() {
+    let mut v1 = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "CEST")], &[(0, 1)]);
+    v1.ttinfos[1].2 = 9;
+    let error = ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)).err().unwrap();
+
+    assert_eq!(error.to_string(),
+               "malformed zoneinfo data: abbreviation index 9 of local time type 1 exceeds the 9 abbreviation bytes");
+
+    v1.ttinfos[1].2 = 8;
+    assert_eq!(ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)).unwrap()
+                   .get_actual_zoneinfo(Timespec::new(0, 0)).unwrap().abbreviation, "");
+}
+
+#[test]
+fn zoneinfo_type_index_out_of_range() {
+    let mut v1 = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "CEST")], &[(0, 1), (1000, 0)]);
+    v1.types[1] = 2;
+    let error = ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)).err().unwrap();
+
+    assert_eq!(error.to_string(),
+               "malformed zoneinfo data: local time type 2 of transition 1 exceeds the 2 local time types");
+
+    let v2 = v1.clone();
+    v1.types[1] = 0;
+    assert!(ZoneInfo::from_bytes(&testdata::tzif(b'2', &v1, &v2, "CET-1")).is_err());
+    assert!(ZoneInfo::from_bytes(&testdata::tzif(b'2', &v2, &v1, "CET-1")).is_ok());
+}
+
+#[test]
+fn zoneinfo_tz_database() {
+    let cet = testdata::Block::new(&[(3600, false, "CET")], &[]);
+    let eet = testdata::Block::new(&[(7200, false, "EET")], &[]);
+    let database = TzDatabase::from_entries(vec![
+        ("Europe/Amsterdam".to_string(), testdata::tzif_v1(&cet)),
+        ("Europe/Helsinki".to_string(), testdata::tzif_v1(&eet)),
+        ("zone.tab".to_string(), b"# no zone".to_vec()),
+    ]);
+
+    assert_eq!(database.locations(), vec!["Europe/Amsterdam", "Europe/Helsinki"]);
+    let zone = database.by_tz("Europe/Helsinki").unwrap();
+    assert_eq!(zone.name(), Some("Europe/Helsinki"));
+    assert_eq!(zone.offset_at_epoch(), Some(7200));
+    assert!(database.by_tz("zone.tab").is_none());
+    assert!(matches!(database.errors()["zone.tab"], ZoneInfoError::InvalidMagic));
+}
+
+#[test]
+fn zoneinfo_group_zones_by_offset_label() {
+    let groups = ZoneInfo::group_zones_by_offset_label(Timespec::new(1700000000, 0));
+
+    assert!(groups["(UTC+01:00)"].contains(&"Amsterdam".to_string()));
+    assert!(groups["(UTC+01:00)"].contains(&"Berlin".to_string()));
+    assert!(groups["(UTC-05:00)"].contains(&"New York".to_string()));
+    assert!(groups["(UTC+05:45)"].contains(&"Kathmandu".to_string()));
+    assert!(groups["(UTC+00:00)"].windows(2).all(|w| w[0] < w[1]));
+}
+
+#[test]
+fn zoneinfo_missing_transition_flags() {
+    let mut v1 = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "CEST")],
+                                      &[(1000000000, 1), (1020000000, 0)]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)).unwrap();
+
+    assert_eq!(zi.section_sizes().transition_flags, 0);
+    for info in zi.get_transitions().values() {
+        assert!(matches!(info.wall_clock_or_standard, TransitionTimeFlag::WallClock));
+        assert!(matches!(info.local_or_universal_time, TransitionTimeFlag::Local));
+    }
+
+    // indicators for the first type only
+    v1.isstd = vec![1];
+    v1.isut = vec![1];
+    let zi = ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)).unwrap();
+    let transitions = zi.get_transitions();
+
+    assert!(matches!(transitions[&Timespec::new(1020000000, 0)].wall_clock_or_standard, TransitionTimeFlag::Standard));
+    assert!(matches!(transitions[&Timespec::new(1020000000, 0)].local_or_universal_time, TransitionTimeFlag::Universal));
+    assert!(matches!(transitions[&Timespec::new(1000000000, 0)].wall_clock_or_standard, TransitionTimeFlag::WallClock));
+    assert!(matches!(transitions[&Timespec::new(1000000000, 0)].local_or_universal_time, TransitionTimeFlag::Local));
+}
+
+#[test]
+fn zoneinfo_zones_with_transition_near() {
+    let mar31 = Timespec::new(civil::days_from_civil(2024, 3, 31) * 86400 + 3600, 0);
+    let zones = ZoneInfo::zones_with_transition_near(mar31, Duration::hours(1));
+
+    assert!(zones.contains(&"Europe/Amsterdam".to_string()));
+    assert!(zones.contains(&"Europe/London".to_string()));
+    assert!(!zones.contains(&"America/New_York".to_string()));
+    assert!(!zones.contains(&"Asia/Kathmandu".to_string()));
+
+    // computed from the POSIX TZ rules
+    let mar27 = Timespec::new(civil::days_from_civil(2050, 3, 27) * 86400 + 3600, 0);
+    let zones = ZoneInfo::zones_with_transition_near(mar27, Duration::hours(1));
+
+    assert!(zones.contains(&"Europe/Amsterdam".to_string()));
+    assert!(!zones.contains(&"America/New_York".to_string()));
+}
+
+#[test]
+fn zoneinfo_from_bytes() {
+    let path = Path::new("/usr/share/zoneinfo/Europe/Amsterdam");
+    let data = std::fs::read(path).unwrap();
+    let zi = ZoneInfo::from_bytes(&data).unwrap();
+
+    assert_eq!(zi.fingerprint(), ZoneInfo::new(path).unwrap().fingerprint());
+    assert_eq!(zi.get_dst_specifier(), "CET-1CEST,M3.5.0,M10.5.0/3");
+    assert_eq!(zi.get_actual_zoneinfo(Timespec::new(1700000000, 0)).unwrap().abbreviation, "CET");
+    assert_eq!(zi.name(), None);
+}
+
+#[test]
+fn zoneinfo_abbreviation_matches() {
+    let zi = ZoneInfo::by_tz("America/New_York").unwrap();
+    let july = Timespec::new(civil::days_from_civil(2025, 7, 1) * 86400, 0);
+
+    assert_eq!(zi.abbreviation_at(july), Some("EDT".to_string()));
+    assert!(zi.abbreviation_matches(july, "EDT"));
+    assert!(!zi.abbreviation_matches(july, "EST"));
+    assert!(!zi.abbreviation_matches(july, "edt"));
+
+    let zi = ZoneInfo::by_tz("Europe/Istanbul").unwrap();
+    for expected in &["+03", "+0300", "<+03>"] {
+        assert!(zi.abbreviation_matches(july, expected));
+    }
+    assert!(!zi.abbreviation_matches(july, "-03"));
+    assert!(!zi.abbreviation_matches(july, "+0330"));
+}
+
+#[test]
+fn zoneinfo_version() {
+    let block = testdata::Block::new(&[(3600, false, "CET")], &[(0, 0)]);
+
+    let zi = ZoneInfo::from_bytes(&testdata::tzif_v1(&block)).unwrap();
+    assert_eq!(zi.version(), TzVersion::V1);
+    assert_eq!(zi.get_dst_specifier(), "");
+
+    let zi = ZoneInfo::from_bytes(&testdata::tzif(b'2', &block, &block, "CET-1")).unwrap();
+    assert_eq!(zi.version(), TzVersion::V2);
+    assert_eq!(zi.get_dst_specifier(), "CET-1");
+
+    let zi = ZoneInfo::from_bytes(&testdata::tzif(b'3', &block, &block, "CET-1")).unwrap();
+    assert_eq!(zi.version(), TzVersion::V3);
+
+    assert_eq!(ZoneInfo::from_posix_tz("CET-1").unwrap().version(), TzVersion::V2);
+    assert!(TzVersion::V1 < TzVersion::V2);
+}
+
+#[test]
+fn zoneinfo_secs_api() {
+    let block = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "CEST")],
+                                     &[(0, 0), (1000, 1), (2000, 0)]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif_v1(&block)).unwrap();
+
+    assert_eq!(zi.offset_at_secs(-1), None);
+    assert_eq!(zi.offset_at_secs(999), Some(3600));
+    assert_eq!(zi.offset_at_secs(1000), Some(7200));
+    assert_eq!(zi.is_dst_at_secs(1500), Some(true));
+    assert_eq!(zi.zoneinfo_at_secs(2000).unwrap().abbreviation, "CET");
+
+    let (time, info) = zi.next_transition_secs(1000).unwrap();
+    assert_eq!(time, 2000);
+    assert_eq!(info.abbreviation, "CET");
+    assert!(zi.next_transition_secs(2000).is_none());
+}
+
+#[test]
+fn zoneinfo_has_64bit_block() {
+    let block = testdata::Block::new(&[(3600, false, "CET")], &[(0, 0)]);
+
+    assert!(!ZoneInfo::has_64bit_block(&testdata::tzif_v1(&block)).unwrap());
+    let data = testdata::tzif(b'2', &block, &block, "CET-1");
+    assert!(ZoneInfo::has_64bit_block(&data).unwrap());
+    // only the first header is needed
+    assert!(ZoneInfo::has_64bit_block(&data[..44]).unwrap());
+    assert!(matches!(ZoneInfo::has_64bit_block(b"TZjf"), Err(ZoneInfoError::InvalidMagic)));
+}
+
+#[test]
+fn zoneinfo_parse_dst_specifier() {
+    let zi = ZoneInfo::by_tz("Europe/Amsterdam").unwrap();
+    let tz = zi.parse_dst_specifier().unwrap().unwrap();
+
+    assert_eq!(tz.std_abbreviation, "CET");
+    assert_eq!(tz.std_ut_offset, 3600);
+    let dst = tz.dst.unwrap();
+    assert_eq!(dst.abbreviation, "CEST");
+    assert_eq!(dst.ut_offset, 7200);
+    assert_eq!(dst.start, PosixRule { date: PosixDate::MonthWeekDay { month: 3, week: 5, weekday: 0 }, time: 7200 });
+    assert_eq!(dst.end, PosixRule { date: PosixDate::MonthWeekDay { month: 10, week: 5, weekday: 0 }, time: 10800 });
+
+    let block = testdata::Block::new(&[(3600, false, "CET")], &[(0, 0)]);
+    assert!(ZoneInfo::from_bytes(&testdata::tzif_v1(&block)).unwrap().parse_dst_specifier().is_none());
+
+    let zi = ZoneInfo::from_bytes(&testdata::tzif(b'2', &block, &block, "CET-1CEST,M3")).unwrap();
+    assert!(matches!(zi.parse_dst_specifier(), Some(Err(ZoneInfoError::InvalidPosixTz(_)))));
+}
+
+#[test]
+fn zoneinfo_beyond_last_transition() {
+    let zi = ZoneInfo::by_tz("Europe/Amsterdam").unwrap();
+    let spring = civil::days_from_civil(2045, 3, 26) * 86400 + 3600;
+    let fall = civil::days_from_civil(2045, 10, 29) * 86400 + 3600;
+
+    assert_eq!(zi.current_date_source(Timespec::new(spring, 0)), DateSource::PosixRule);
+    assert_eq!(zi.get_actual_zoneinfo(Timespec::new(spring - 1, 0)).unwrap().abbreviation, "CET");
+    let info = zi.get_actual_zoneinfo(Timespec::new(spring, 0)).unwrap();
+    assert_eq!((info.abbreviation.as_str(), info.ut_offset, info.isdst), ("CEST", 7200, true));
+    assert_eq!(zi.get_actual_zoneinfo(Timespec::new(fall - 1, 0)).unwrap().abbreviation, "CEST");
+    assert_eq!(zi.get_actual_zoneinfo(Timespec::new(fall, 0)).unwrap().ut_offset, 3600);
+
+    let (next, info) = zi.get_next_transition_time(Timespec::new(spring - 86400, 0)).unwrap();
+    assert_eq!((next.sec, info.isdst), (spring, true));
+    let (next, info) = zi.get_next_transition_time(Timespec::new(spring, 0)).unwrap();
+    assert_eq!((next.sec, info.isdst), (fall, false));
+
+    let snapshot = zi.snapshot_offset_around(Timespec::new(spring + 86400, 0));
+    assert_eq!((snapshot.offset, snapshot.start.sec), (7200, spring));
+    assert_eq!(snapshot.end, Some(Timespec::new(fall, 0)));
+
+    let mut cached = zi.cached_offset();
+    assert_eq!(cached.offset_at(Timespec::new(fall - 1, 0)), Some(7200));
+    assert_eq!(cached.offset_at(Timespec::new(fall, 0)), Some(3600));
+}
+
+#[test]
+fn zoneinfo_with_override() {
+    let v1 = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "CEST")],
+                                  &[(1000000000, 1), (1020000000, 0)]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)).unwrap();
+
+    // shadow an existing transition
+    let fixed = zi.with_override(Timespec::new(1020000000, 0), 10800, "XST", false).unwrap();
+    assert_eq!(fixed.get_actual_zoneinfo(Timespec::new(1020000000, 0)).unwrap().abbreviation, "XST");
+    assert_eq!(fixed.get_transitions().len(), 2);
+    assert_eq!(fixed.section_sizes().local_time_types, 3 * 6);
+    assert_eq!(zi.get_actual_zoneinfo(Timespec::new(1020000000, 0)).unwrap().abbreviation, "CET");
+
+    // insert a new transition reusing an existing type
+    let fixed = zi.with_override(Timespec::new(1010000000, 0), 3600, "CET", false).unwrap();
+    assert_eq!(fixed.get_transitions().len(), 3);
+    assert_eq!(fixed.section_sizes().local_time_types, 2 * 6);
+    assert_eq!(fixed.get_actual_zoneinfo(Timespec::new(1009999999, 0)).map(|info| info.ut_offset), Some(7200));
+    assert_eq!(fixed.get_actual_zoneinfo(Timespec::new(1010000000, 0)).map(|info| info.ut_offset), Some(3600));
+}
+
+#[test]
+fn zoneinfo_with_override_beyond_table() {
+    let v1 = testdata::Block::new(&[(0, false, "")], &[]);
+    let v2 = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "CEST")],
+                                  &[(1000000000, 1), (1020000000, 0)]);
+    let day = |year, month, day| civil::days_from_civil(year, month, day) * 86400;
+    let at = Timespec::new(day(2024, 7, 1), 0);
+    let october27 = day(2024, 10, 27) + 3600;
+
+    // the override lasts until the next transition of the POSIX TZ rule
+    let zi = ZoneInfo::from_bytes(&testdata::tzif(b'2', &v1, &v2, "CET-1CEST,M3.5.0,M10.5.0/3")).unwrap();
+    let fixed = zi.with_override(at, 10800, "XST", false).unwrap();
+    assert_eq!(fixed.get_offset_at(at), Some(10800));
+    assert_eq!(fixed.get_offset_at(Timespec::new(october27 - 1, 0)), Some(10800));
+    assert_eq!(fixed.get_abbreviation_at(Timespec::new(october27, 0)), Some("CET"));
+    assert_eq!(fixed.get_offset_at(Timespec::new(day(2025, 7, 1), 0)), Some(7200));
+    assert_eq!(fixed.latest_transition(), Some(Timespec::new(october27, 0)));
+    assert_eq!(fixed.get_dst_specifier(), zi.get_dst_specifier());
+
+    // without transitions of the rule the override stays in effect
+    let zi = ZoneInfo::from_bytes(&testdata::tzif(b'2', &v1, &v2, "CET-1")).unwrap();
+    let fixed = zi.with_override(at, 10800, "XST", false).unwrap();
+    assert_eq!(fixed.get_offset_at(Timespec::new(day(2050, 1, 1), 0)), Some(10800));
+    assert_eq!(fixed.get_dst_specifier(), "");
+}
+
+#[test]
+fn zoneinfo_with_override_all_types_used() {
+    let v1 = testdata::Block {
+        times: vec![0],
+        types: vec![0],
+        ttinfos: (0..256).map(|i| (i * 60, i % 2 == 1, 0)).collect(),
+        chars: b"X\0".to_vec(),
+        ..testdata::Block::default()
+    };
+    let zi = ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)).unwrap();
+    let at = Timespec::new(1000, 0);
+
+    match zi.with_override(at, 5430, "NEW", false) {
+        Err(ZoneInfoError::Malformed(message)) => assert_eq!(message, "all 256 local time types are in use"),
+        _ => panic!("override needing a 257th type accepted")
+    }
+
+    assert_eq!(zi.with_override(at, 60, "X", true).unwrap().get_offset_at(at), Some(60));
+}
+
+#[test]
+fn zoneinfo_raw_abbreviation_table() {
+    let mut v1 = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "CEST")], &[(0, 0)]);
+    // a third type sharing the suffix of CEST and an unreferenced string
+    v1.ttinfos.push((0, false, 6));
+    v1.chars.extend(b"UNUSED\0");
+    let zi = ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)).unwrap();
+
+    assert_eq!(zi.raw_abbreviation_table(), vec!["CET", "CEST", "UNUSED"]);
+
+    let zi = ZoneInfo::from_posix_tz("CET-1CEST").unwrap();
+    assert_eq!(zi.raw_abbreviation_table(), vec!["CET", "CEST"]);
+    let zi = zi.with_override(Timespec::new(0, 0), 0, "UTC", false).unwrap();
+    assert_eq!(zi.raw_abbreviation_table(), vec!["CET", "CEST", "UTC"]);
+}
+
+#[test]
+fn zoneinfo_get_offset_at() {
+    let v1 = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "CEST")],
+                                  &[(1000000000, 1), (1020000000, 0)]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)).unwrap();
+
+    assert_eq!(zi.get_offset_at(Timespec::new(999999999, 0)), None);
+    assert_eq!(zi.get_offset_at(Timespec::new(1000000000, 0)), Some(7200));
+    assert_eq!(zi.get_offset_at(Timespec::new(1020000000, 0)), Some(3600));
+
+    let zi = ZoneInfo::by_tz("America/New_York").unwrap();
+    for &year in &[2020, 2090] {
+        for &month in &[1, 7] {
+            let at = Timespec::new(civil::days_from_civil(year, month, 1) * 86400, 0);
+            assert_eq!(zi.get_offset_at(at), zi.get_actual_zoneinfo(at).map(|info| info.ut_offset));
+        }
+    }
+}
+
+#[test]
+fn zoneinfo_get_abbreviation_at() {
+    let zi = ZoneInfo::by_tz("Europe/Amsterdam").unwrap();
+    let summer = Timespec::new(civil::days_from_civil(2024, 7, 1) * 86400, 0);
+    let winter = Timespec::new(civil::days_from_civil(2024, 1, 1) * 86400, 0);
+
+    assert_eq!(zi.get_abbreviation_at(summer), Some("CEST"));
+    assert_eq!(zi.get_abbreviation_at(winter), Some("CET"));
+    let far = Timespec::new(civil::days_from_civil(2090, 7, 1) * 86400, 0);
+    assert_eq!(zi.get_abbreviation_at(far), Some("CEST"));
+
+    let v1 = testdata::Block::new(&[(3600, false, "CET")], &[(1000000000, 0)]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)).unwrap();
+    assert_eq!(zi.get_abbreviation_at(Timespec::new(0, 0)), None);
+}
+
+#[test]
+fn zoneinfo_minimize() {
+    let since = Timespec::new(civil::days_from_civil(2000, 6, 1) * 86400, 0);
+
+    for location in &["Europe/Amsterdam", "America/Sao_Paulo", "Asia/Tokyo"] {
+        let zi = ZoneInfo::by_tz(location).unwrap();
+        let slim = zi.minimize(since);
+
+        assert!(slim.get_transitions().len() < zi.get_transitions().len());
+        assert!(slim.get_transitions().keys().skip(1).all(|&time| time > since));
+        assert_eq!(slim.section_sizes().transitions, slim.get_transitions().len() * 9);
+        assert_eq!(slim.get_dst_specifier(), zi.get_dst_specifier());
+        for step in 0..2000 {
+            let at = since + Duration::hours(step * 307);
+            assert_eq!(slim.get_offset_at(at), zi.get_offset_at(at), "{} at {:?}", location, at);
+        }
+        assert_eq!(slim.get_offset_at(Timespec::new(0, 0)), zi.get_offset_at(since));
+    }
+}
+
+#[test]
+fn zoneinfo_dedup_types() {
+    let v1 = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "CEST"), (3600, false, "CET"), (7200, true, "CEST")],
+                                  &[(1000000000, 1), (1020000000, 2), (1040000000, 3), (1060000000, 0)]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)).unwrap();
+    assert!(zi.has_redundant_types());
+
+    let deduped = zi.dedup_types();
+    assert!(!deduped.has_redundant_types());
+    assert_eq!(deduped.section_sizes().local_time_types, 2 * 6);
+    assert_eq!(deduped.transition_table(), vec![
+        (1000000000, 7200, true, "CEST".to_string()),
+        (1020000000, 3600, false, "CET".to_string()),
+        (1040000000, 7200, true, "CEST".to_string()),
+        (1060000000, 3600, false, "CET".to_string()),
+    ]);
+    assert_eq!(deduped.transition_table(), zi.transition_table());
+    assert_eq!(deduped.fingerprint(), zi.fingerprint());
+
+    let zi = ZoneInfo::by_tz("Europe/Amsterdam").unwrap();
+    assert_eq!(zi.dedup_types().transition_table(), zi.transition_table());
+}
+
+#[test]
+fn zoneinfo_cached_transitions() {
+    let v1 = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "CEST")],
+                                  &[(1000000000, 1), (1020000000, 0)]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)).unwrap();
+
+    assert_eq!(zi.get_transitions().len(), 2);
+    assert_eq!(zi.get_transitions().len(), 2);
+
+    // derived zones don't reuse the transitions of the original
+    let fixed = zi.with_override(Timespec::new(1010000000, 0), 3600, "CET", false).unwrap();
+    assert_eq!(fixed.get_transitions().len(), 3);
+    let (next, _) = fixed.get_next_transition_time(Timespec::new(1000000000, 0)).unwrap();
+    assert_eq!(next, Timespec::new(1010000000, 0));
+    assert_eq!(zi.minimize(Timespec::new(1010000000, 0)).get_transitions().len(), 2);
+    assert_eq!(zi.get_transitions().len(), 2);
+}
+
+#[test]
+fn zoneinfo_many_transitions() {
+    let transitions: Vec<(i64, u8)> = (0..1000).map(|i| (i * 1000 - 500000, (i % 3) as u8)).collect();
+    let v1 = testdata::Block::new(&[(0, false, "A"), (3600, true, "B"), (7200, false, "C")], &transitions);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)).unwrap();
+
+    for &(time, index) in &transitions {
+        let before = transitions.iter().rev().find(|&&(t, _)| t < time).map(|&(_, i)| i as i32 * 3600);
+        assert_eq!(zi.get_offset_at(Timespec::new(time - 1, 0)), before);
+        assert_eq!(zi.get_offset_at(Timespec::new(time, 0)), Some(index as i32 * 3600));
+        assert_eq!(zi.get_offset_at(Timespec::new(time, 1)), Some(index as i32 * 3600));
+
+        let next = transitions.iter().find(|&&(t, _)| t > time).map(|&(t, _)| Timespec::new(t, 0));
+        assert_eq!(zi.get_next_transition_time(Timespec::new(time, 0)).map(|(t, _)| t), next);
+        assert_eq!(zi.get_next_transition_time(Timespec::new(time - 1, 0)).map(|(t, _)| t),
+                   Some(Timespec::new(time, 0)));
+    }
+
+    let v1 = testdata::Block::new(&[(0, false, "A")], &[(2000, 0), (1000, 0)]);
+    assert!(matches!(ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)), Err(ZoneInfoError::Malformed(_))));
+}
+
+#[test]
+fn zoneinfo_is_prehistoric() {
+    let v1 = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "CEST")],
+                                  &[(1000000000, 1), (1020000000, 0)]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)).unwrap();
+
+    assert!(zi.is_prehistoric(Timespec::new(999999999, 999999999)));
+    assert!(!zi.is_prehistoric(Timespec::new(1000000000, 0)));
+    assert!(!zi.is_prehistoric(Timespec::new(2000000000, 0)));
+
+    let v1 = testdata::Block::new(&[(0, false, "UTC")], &[]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)).unwrap();
+    assert!(!zi.is_prehistoric(Timespec::new(i64::MIN + 1, 0)));
+}
+
+#[test]
+fn zoneinfo_offsets_in_range() {
+    let year = |y| Timespec::new(civil::days_from_civil(y, 1, 1) * 86400, 0);
+    let zi = ZoneInfo::by_tz("Europe/Amsterdam").unwrap();
+
+    // Amsterdam time (+00:20) until May 1940, CET and CEST afterwards
+    assert_eq!(zi.offsets_in_range(year(1940), year(1950)), vec![1200, 3600, 7200]);
+    assert_eq!(zi.offsets_in_range(year(1960), year(1970)), vec![3600]);
+    assert_eq!(zi.offsets_in_range(year(2080), year(2090)), vec![3600, 7200]);
+    assert!(zi.offsets_in_range(year(2000), year(2000)).is_empty());
+
+    let zi = ZoneInfo::by_tz("Asia/Tokyo").unwrap();
+    assert_eq!(zi.offsets_in_range(year(2000), year(2010)), vec![32400]);
+}
+
+#[test]
+fn zoneinfo_get_previous_transition_time() {
+    let v1 = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "CEST")],
+                                  &[(1000000000, 1), (1020000000, 0)]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)).unwrap();
+
+    let (time, info) = zi.get_previous_transition_time(Timespec::new(1000000001, 0)).unwrap();
+    assert_eq!((time, info.abbreviation.as_str()), (Timespec::new(1000000000, 0), "CEST"));
+    assert!(zi.get_previous_transition_time(Timespec::new(1000000000, 0)).is_none());
+    let (time, _) = zi.get_previous_transition_time(Timespec::new(1020000000, 0)).unwrap();
+    assert_eq!(time, Timespec::new(1000000000, 0));
+    let (time, _) = zi.get_previous_transition_time(Timespec::new(1020000000, 1)).unwrap();
+    assert_eq!(time, Timespec::new(1020000000, 0));
+
+    // the initial transition of zones without transitions isn't returned
+    let v1 = testdata::Block::new(&[(0, false, "UTC")], &[]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)).unwrap();
+    assert!(zi.get_previous_transition_time(Timespec::new(0, 0)).is_none());
+
+    let zi = ZoneInfo::by_tz("Europe/Amsterdam").unwrap();
+    let spring = civil::days_from_civil(2045, 3, 26) * 86400 + 3600;
+    let (time, info) = zi.get_previous_transition_time(Timespec::new(spring + 86400, 0)).unwrap();
+    assert_eq!((time.sec, info.isdst), (spring, true));
+    let (time, info) = zi.get_previous_transition_time(Timespec::new(spring, 0)).unwrap();
+    assert_eq!((time.sec, info.isdst), (civil::days_from_civil(2044, 10, 30) * 86400 + 3600, false));
+}
+
+#[test]
+fn zoneinfo_duration_until_next_transition() {
+    let v1 = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "CEST")],
+                                  &[(1000000000, 1), (1020000000, 0)]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)).unwrap();
+
+    assert_eq!(zi.duration_until_next_transition(Timespec::new(1000000000, 0)),
+               Some(Duration::seconds(20000000)));
+    assert_eq!(zi.duration_until_next_transition(Timespec::new(1019999999, 500000000)),
+               Some(Duration::milliseconds(500)));
+    assert_eq!(zi.duration_until_next_transition(Timespec::new(1020000000, 0)), None);
+    assert_eq!(zi.duration_until_next_transition(Timespec::new(i64::MIN, 0)), None);
+
+    let zi = ZoneInfo::by_tz("Europe/Amsterdam").unwrap();
+    let spring = civil::days_from_civil(2045, 3, 26) * 86400 + 3600;
+    assert_eq!(zi.duration_until_next_transition(Timespec::new(spring - 3600, 0)), Some(Duration::hours(1)));
+    assert_eq!(ZoneInfo::by_tz("Asia/Tokyo").unwrap().duration_until_next_transition(Timespec::new(spring, 0)), None);
+}
+
+#[test]
+fn zoneinfo_abbreviation_at_truncated() {
+    let v1 = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "ÄÖÜT")],
+                                  &[(1000000000, 0), (1020000000, 1)]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)).unwrap();
+
+    assert_eq!(zi.abbreviation_at_truncated(Timespec::new(1000000000, 0), 8), Some("CET".to_string()));
+    assert_eq!(zi.abbreviation_at_truncated(Timespec::new(1000000000, 0), 2), Some("CE".to_string()));
+    assert_eq!(zi.abbreviation_at_truncated(Timespec::new(1020000000, 0), 3), Some("ÄÖÜ".to_string()));
+    assert_eq!(zi.abbreviation_at_truncated(Timespec::new(1020000000, 0), 0), Some("".to_string()));
+    assert_eq!(zi.abbreviation_at_truncated(Timespec::new(0, 0), 3), None);
+}
+
+#[test]
+fn zoneinfo_equivalent_zones() {
+    let zones = ZoneInfo::equivalent_zones("Asia/Tokyo");
+
+    assert!(zones.contains(&"Japan".to_string()));
+    assert!(!zones.contains(&"Asia/Tokyo".to_string()));
+    assert!(!zones.contains(&"Asia/Seoul".to_string()));
+    assert!(ZoneInfo::equivalent_zones("Nowhere/Atlantis").is_empty());
+}
+
+#[test]
+fn zoneinfo_transitions_iterator() {
+    let zi = ZoneInfo::by_tz("Europe/Amsterdam").unwrap();
+    let map = zi.get_transitions();
+
+    assert_eq!(zi.transitions().count(), map.len());
+    for ((time, info), (map_time, map_info)) in zi.transitions().zip(map.iter()) {
+        assert_eq!(time, *map_time);
+        assert_eq!((info.ut_offset, info.isdst, &info.abbreviation),
+                   (map_info.ut_offset, map_info.isdst, &map_info.abbreviation));
+    }
+
+    let summer: Vec<Timespec> = zi.transitions()
+                                  .filter(|(time, info)| info.isdst && time.sec >= 1700000000)
+                                  .map(|(time, _)| time)
+                                  .take(1)
+                                  .collect();
+    assert_eq!(summer, vec![Timespec::new(1711846800, 0)]);
+}
+
+#[test]
+fn zoneinfo_get_transitions_in_range() {
+    let v1 = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "CEST")],
+                                  &[(1000000000, 1), (1020000000, 0), (1040000000, 1)]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)).unwrap();
+    let times = |start, end| -> Vec<i64> {
+        zi.get_transitions_in_range(Timespec::new(start, 0), Timespec::new(end, 0))
+          .into_iter()
+          .map(|(time, _)| time.sec)
+          .collect()
+    };
+
+    assert_eq!(times(1000000000, 1040000000), vec![1000000000, 1020000000]);
+    assert_eq!(times(1000000001, 1040000001), vec![1020000000, 1040000000]);
+    assert_eq!(times(0, 1000000000), Vec::<i64>::new());
+    assert_eq!(times(1040000000, 1000000000), Vec::<i64>::new());
+
+    let v1 = testdata::Block::new(&[(0, false, "UTC")], &[]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)).unwrap();
+    assert!(zi.get_transitions_in_range(Timespec::new(i64::MIN + 1, 0), Timespec::new(0, 0)).is_empty());
+    assert_eq!(zi.get_transitions_in_range(Timespec::new(i64::MIN, 0), Timespec::new(0, 0)).len(), 1);
+}
+
+#[test]
+fn zoneinfo_prefer_64bit() {
+    let v1 = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "CEST")], &[(1000000000, 1)]);
+    let v2 = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "CEST")],
+                                  &[(1000000000, 1), (3000000000, 0)]);
+    let data = testdata::tzif(b'2', &v1, &v2, "CET-1");
+    let beyond_2038 = Timespec::new(3000000000, 0);
+
+    let zi = ZoneInfo::from_bytes(&data).unwrap();
+    assert_eq!(zi.get_transitions().len(), 2);
+    assert_eq!(zi.get_offset_at(beyond_2038), Some(3600));
+
+    let options = ParseOptions { prefer_64bit: false, ..ParseOptions::default() };
+    let zi = ZoneInfo::from_bytes_with_options(&data, &options).unwrap();
+    assert_eq!(zi.get_transitions().len(), 1);
+    assert_eq!(zi.get_dst_specifier(), "CET-1");
+}
+
+#[test]
+fn zoneinfo_local_zoneinfo_tz_variable() {
+    let local = |tz: Option<&str>| ZoneInfo::for_tz_variable(tz.map(|tz| tz.to_string())).unwrap();
+
+    assert_eq!(local(Some("Europe/Berlin")).name(), Some("Europe/Berlin"));
+    assert_eq!(local(Some(":/usr/share/zoneinfo/Asia/Tokyo")).fingerprint(),
+               ZoneInfo::by_tz("Asia/Tokyo").unwrap().fingerprint());
+    assert_eq!(local(Some("XST-5")).get_offset_at(Timespec::new(0, 0)), Some(18000));
+    assert!(ZoneInfo::for_tz_variable(Some("Not/A_Zone".to_string())).is_err());
+
+    let etc_localtime = ZoneInfo::new(Path::new("/etc/localtime")).unwrap().fingerprint();
+    assert_eq!(local(None).fingerprint(), etc_localtime);
+    assert_eq!(local(Some("")).fingerprint(), etc_localtime);
+}
+
+#[test]
+fn zoneinfo_tzdir() {
+    let dir = std::env::temp_dir().join("zoneinfo_tzdir");
+    std::fs::create_dir_all(dir.join("Custom")).unwrap();
+    std::fs::copy("/usr/share/zoneinfo/Asia/Tokyo", dir.join("Custom/Zone")).unwrap();
+
+    let source = FsZoneSource::for_tzdir(Some(dir.clone().into_os_string()));
+    assert_eq!(ZoneInfo::get_tz_locations_from(&source), vec!["Custom/Zone".to_string()]);
+    assert_eq!(ZoneInfo::by_tz_from(&source, "Custom/Zone").unwrap().get_dst_specifier(), "JST-9");
+    assert!(ZoneInfo::by_tz_from(&source, "Asia/Tokyo").is_err());
+
+    for tzdir in &[None, Some(std::ffi::OsString::new())] {
+        let source = FsZoneSource::for_tzdir(tzdir.clone());
+        assert!(ZoneInfo::by_tz_from(&source, "Asia/Tokyo").is_ok());
+    }
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn zoneinfo_by_tz_in() {
+    let empty = std::env::temp_dir().join("zoneinfo_by_tz_in_empty");
+    let dir = std::env::temp_dir().join("zoneinfo_by_tz_in");
+    std::fs::create_dir_all(&empty).unwrap();
+    std::fs::create_dir_all(dir.join("Opt")).unwrap();
+    std::fs::copy("/usr/share/zoneinfo/Asia/Tokyo", dir.join("Opt/Tokyo")).unwrap();
+    let dirs = [empty.as_path(), dir.as_path()];
+
+    assert_eq!(ZoneInfo::get_tz_locations_in(&dirs), vec!["Opt/Tokyo".to_string()]);
+    let zi = ZoneInfo::by_tz_in("Opt/Tokyo", &dirs).unwrap();
+    assert_eq!((zi.name(), zi.get_dst_specifier().as_str()), (Some("Opt/Tokyo"), "JST-9"));
+    assert!(ZoneInfo::by_tz_in("Asia/Tokyo", &dirs).is_err());
+    assert!(ZoneInfo::get_tz_locations_in(&[]).is_empty());
+
+    for dir in &[&empty, &dir] {
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
+
+#[test]
+fn zoneinfo_locations_without_mirrors() {
+    let dir = std::env::temp_dir().join("zoneinfo_locations_without_mirrors");
+    for sub in &["Asia", "posix/Asia", "right/Asia"] {
+        std::fs::create_dir_all(dir.join(sub)).unwrap();
+        std::fs::copy("/usr/share/zoneinfo/Asia/Tokyo", dir.join(sub).join("Tokyo")).unwrap();
+    }
+    for file in &["zone.tab", "zone1970.tab", "iso3166.tab", "leapseconds", "tzdata.zi"] {
+        std::fs::write(dir.join(file), "# metadata\n").unwrap();
+    }
+
+    let source = FsZoneSource::new(&dir);
+    assert_eq!(ZoneInfo::get_tz_locations_from(&source), vec!["Asia/Tokyo".to_string()]);
+    assert!(ZoneInfo::by_tz_from(&source, "posix/Asia/Tokyo").is_err());
+
+    let all = ZoneInfo::get_tz_locations_from(&source.including_all_files());
+    assert_eq!(all.len(), 8);
+    assert!(all.contains(&"right/Asia/Tokyo".to_string()));
+    assert!(all.contains(&"zone.tab".to_string()));
+
+    assert!(!ZoneInfo::get_tz_locations().iter().any(|location| location.starts_with("right/")));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn zoneinfo_by_tz_right() {
+    let posix = ZoneInfo::by_tz("Europe/Amsterdam").unwrap();
+    let right = ZoneInfo::by_tz_right("Europe/Amsterdam").unwrap();
+
+    assert!(posix.get_leap_second_transitions().is_empty());
+    assert!(right.get_leap_second_transitions().len() >= 27);
+    assert_eq!(right.name(), Some("Europe/Amsterdam"));
+    assert_eq!(right.get_dst_specifier(), "");
+
+    // the listed transitions of 2020 are 27 leap seconds later
+    let year = |y| Timespec::new(civil::days_from_civil(y, 1, 1) * 86400, 0);
+    let transitions = |zi: &ZoneInfo| zi.get_transitions_in_range(year(2020), year(2021));
+    assert_eq!(transitions(&right)[0].0.sec - transitions(&posix)[0].0.sec, 27);
+
+    assert!(ZoneInfo::by_tz_right("Nowhere/Atlantis").is_err());
+}
+
+#[test]
+fn zoneinfo_zone_metadata() {
+    let zones = parse_zone_tab("# tzdb timezone descriptions\n\
+                                NL\t+5222+00454\tEurope/Amsterdam\n\
+                                US\t+404251-0740023\tAmerica/New_York\tEastern (most areas)\n\
+                                XX\tbogus\tNowhere/Atlantis\n\
+                                XX\t\tNowhere/Atlantis\n");
+
+    assert_eq!(zones.len(), 2);
+    assert_eq!(zones[0].location, "Europe/Amsterdam");
+    assert_eq!(zones[0].country_code, "NL");
+    assert!((zones[0].latitude - (52.0 + 22.0 / 60.0)).abs() < 1e-9);
+    assert!((zones[0].longitude - (4.0 + 54.0 / 60.0)).abs() < 1e-9);
+    assert_eq!(zones[0].comment, None);
+    assert!((zones[1].latitude - (40.0 + 42.0 / 60.0 + 51.0 / 3600.0)).abs() < 1e-9);
+    assert!((zones[1].longitude + (74.0 + 23.0 / 3600.0)).abs() < 1e-9);
+    assert_eq!(zones[1].comment, Some("Eastern (most areas)".to_string()));
+
+    let meta = ZoneInfo::zone_metadata("Asia/Tokyo").unwrap();
+    assert_eq!(meta.country_code, "JP");
+    assert!(meta.latitude > 35.0 && meta.longitude > 139.0);
+    assert!(ZoneInfo::zone_metadata("Japan").is_none());
+    assert!(ZoneInfo::all_zone_metadata().len() > 300);
+}
+
+#[test]
+fn zoneinfo_canonical_name() {
+    let dir = std::env::temp_dir().join("zoneinfo_canonical_name");
+    std::fs::create_dir_all(dir.join("Asia")).unwrap();
+    std::fs::create_dir_all(dir.join("Legacy")).unwrap();
+    std::fs::copy("/usr/share/zoneinfo/Asia/Tokyo", dir.join("Asia/Tokyo")).unwrap();
+    let _ = std::fs::remove_file(dir.join("Legacy/Japan"));
+    std::os::unix::fs::symlink("../Asia/Tokyo", dir.join("Legacy/Japan")).unwrap();
+    let dirs = [dir.as_path()];
+
+    assert_eq!(canonical_name_in("Legacy/Japan", &dirs), Some("Asia/Tokyo".to_string()));
+    assert_eq!(canonical_name_in("Asia/Tokyo", &dirs), Some("Asia/Tokyo".to_string()));
+    let absolute = dir.join("Legacy/Japan");
+    assert_eq!(canonical_name_in(absolute.to_str().unwrap(), &dirs), Some("Asia/Tokyo".to_string()));
+    assert_eq!(canonical_name_in("Asia", &dirs), None);
+    assert_eq!(canonical_name_in("Nowhere/Atlantis", &dirs), None);
+    assert_eq!(canonical_name_in("/etc/hostname", &dirs), None);
+
+    assert_eq!(ZoneInfo::canonical_name("US/Eastern"), Some("America/New_York".to_string()));
+    assert!(ZoneInfo::canonical_name("/etc/localtime").is_some());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn zoneinfo_leap_seconds_at() {
+    let zi = ZoneInfo::by_tz_right("Etc/UTC").unwrap();
+    // the leap second at the end of 2016, counted in right/ timestamps
+    let (&time, &correction) = zi.get_leap_second_transitions()
+                                 .iter()
+                                 .find(|&(time, _)| time.sec > 1483228800)
+                                 .unwrap();
+
+    assert_eq!((time.sec, correction), (1483228800 + 26, 27));
+    assert_eq!(zi.leap_seconds_at(Timespec::new(time.sec - 1, 999999999)), 26);
+    assert_eq!(zi.leap_seconds_at(time), 27);
+    assert_eq!(zi.leap_seconds_at(Timespec::new(0, 0)), 0);
+    assert_eq!(ZoneInfo::by_tz("Etc/UTC").unwrap().leap_seconds_at(time), 0);
+}
+
+#[test]
+fn zoneinfo_has_dst() {
+    assert!(ZoneInfo::by_tz("Europe/Amsterdam").unwrap().has_dst());
+    assert!(!ZoneInfo::by_tz("Etc/UTC").unwrap().has_dst());
+    assert!(ZoneInfo::from_posix_tz_only("CET-1CEST,M3.5.0,M10.5.0/3", 2000..2001).unwrap().has_dst());
+
+    // an unreferenced daylight saving time type counts as well
+    let v1 = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "CEST")], &[(0, 0)]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)).unwrap();
+    assert!(zi.has_dst());
+    assert!(!zi.observes_dst());
+
+    let zi = ZoneInfo::by_tz("Europe/Amsterdam").unwrap();
+    let far = Timespec::new(civil::days_from_civil(2090, 7, 1) * 86400, 0);
+    assert_eq!(zi.is_dst_at(far), Some(true));
+}
+
+#[test]
+fn zoneinfo_get_standard_offset_at() {
+    let zi = ZoneInfo::by_tz("Europe/Amsterdam").unwrap();
+    let summer = Timespec::new(civil::days_from_civil(2024, 7, 1) * 86400, 0);
+    let far = Timespec::new(civil::days_from_civil(2090, 7, 1) * 86400, 0);
+
+    assert_eq!(zi.get_standard_offset_at(summer), Some(3600));
+    assert_eq!(zi.get_offset_at(summer).unwrap() - zi.get_standard_offset_at(summer).unwrap(), 3600);
+    assert_eq!(zi.get_standard_offset_at(far), Some(3600));
+    assert_eq!(zi.standard_offset(), Some(3600));
+
+    // daylight saving time right from the first transition
+    let v1 = testdata::Block::new(&[(-10800, true, "-03"), (-14400, false, "-04")],
+                                  &[(1000000000, 0), (1020000000, 1)]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)).unwrap();
+    assert_eq!(zi.get_standard_offset_at(Timespec::new(1000000000, 0)), Some(-14400));
+    assert_eq!(zi.get_standard_offset_at(Timespec::new(0, 0)), None);
+    assert_eq!(zi.standard_offset(), Some(-14400));
+}
+
+#[test]
+fn zoneinfo_dst_savings_at() {
+    let day = |y, m, d| Timespec::new(civil::days_from_civil(y, m, d) * 86400, 0);
+    let zi = ZoneInfo::by_tz("Europe/Amsterdam").unwrap();
+    assert_eq!(zi.dst_savings_at(day(2024, 7, 1)), Some(3600));
+    assert_eq!(zi.dst_savings_at(day(2024, 1, 1)), Some(0));
+    assert_eq!(zi.dst_savings_at(day(2090, 7, 1)), Some(3600));
+
+    // Lord Howe Island advances its clocks by half an hour
+    let zi = ZoneInfo::by_tz("Australia/Lord_Howe").unwrap();
+    assert_eq!(zi.dst_savings_at(day(2024, 1, 1)), Some(1800));
+    assert_eq!(zi.dst_savings_at(day(2024, 7, 1)), Some(0));
+
+    let v1 = testdata::Block::new(&[(3600, false, "CET")], &[(1000000000, 0)]);
+    assert_eq!(ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)).unwrap().dst_savings_at(Timespec::new(0, 0)), None);
+}
+
+#[test]
+fn zoneinfo_local_to_utc_beyond_last_transition() {
+    let zi = ZoneInfo::by_tz("Europe/Amsterdam").unwrap();
+    let local = |y, m, d, h: i64, mi: i64| Timespec::new(civil::days_from_civil(y, m, d) * 86400 + h * 3600 + mi * 60, 0);
+
+    // spring forward: 02:00 becomes 03:00 on March 26th, 2045
+    assert_eq!(zi.local_to_utc(local(2045, 3, 26, 2, 30)), LocalResult::None);
+    assert_eq!(zi.local_to_utc(local(2045, 3, 26, 3, 30)),
+               LocalResult::Single(local(2045, 3, 26, 1, 30)));
+
+    // fall back: 03:00 becomes 02:00 on October 29th, 2045
+    assert_eq!(zi.local_to_utc(local(2045, 10, 29, 2, 30)),
+               LocalResult::Ambiguous(local(2045, 10, 29, 0, 30), local(2045, 10, 29, 1, 30)));
+    assert_eq!(zi.local_to_utc(local(2045, 7, 1, 12, 0)), LocalResult::Single(local(2045, 7, 1, 10, 0)));
+}
+
+#[test]
+fn zoneinfo_local_time_gap_and_overlap() {
+    let zi = ZoneInfo::by_tz("Europe/Amsterdam").unwrap();
+    let local = |m, d, h: i64, mi: i64| Timespec::new(civil::days_from_civil(2024, m, d) * 86400 + h * 3600 + mi * 60, 0);
+
+    assert!(zi.is_local_time_gap(local(3, 31, 2, 30)));
+    assert!(!zi.is_local_time_overlap(local(3, 31, 2, 30)));
+
+    assert!(zi.is_local_time_overlap(local(10, 27, 2, 30)));
+    assert!(!zi.is_local_time_gap(local(10, 27, 2, 30)));
+
+    for normal in &[local(3, 31, 3, 0), local(10, 27, 3, 0), local(7, 1, 12, 0)] {
+        assert!(!zi.is_local_time_gap(*normal));
+        assert!(!zi.is_local_time_overlap(*normal));
+    }
+}
+
+#[test]
+fn zoneinfo_local_to_utc_extreme_timestamps() {
+    let zi = ZoneInfo::by_tz("Europe/Amsterdam").unwrap();
+
+    // long before the first transition no offset is known
+    assert_eq!(zi.local_to_utc(Timespec::new(i64::MIN, 0)), LocalResult::None);
+    assert!(zi.is_local_time_gap(Timespec::new(i64::MIN + 1, 0)));
+    let latest = zi.local_to_utc(Timespec::new(i64::MAX, 0));
+    assert!(matches!(latest, LocalResult::Single(utc) if utc.sec == i64::MAX - 3600 || utc.sec == i64::MAX - 7200));
+    assert!(!zi.is_local_time_overlap(Timespec::new(i64::MAX - 1, 0)));
+    // the offset doesn't fit when mapping the latest local time to UTC
+    let utc = ZoneInfo::from_bytes(&testdata::tzif_v1(&testdata::Block::new(&[(-3600, false, "W")], &[]))).unwrap();
+    assert_eq!(utc.local_to_utc(Timespec::new(i64::MAX, 0)), LocalResult::None);
+    assert_eq!(utc.local_to_utc(Timespec::new(0, 0)), LocalResult::Single(Timespec::new(3600, 0)));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn zoneinfo_serde() {
+    let v1 = testdata::Block::new(&[(3600, false, "CET"), (7200, true, "CEST")],
+                                  &[(1000000000, 1), (1020000000, 0)]);
+    let zi = ZoneInfo::from_bytes(&testdata::tzif_v1(&v1)).unwrap();
+    let info = zi.get_actual_zoneinfo(Timespec::new(1000000000, 0)).unwrap();
+    let json = serde_json::to_string(&info).unwrap();
+
+    assert_eq!(json, concat!(r#"{"ut_offset":7200,"isdst":true,"abbreviation":"CEST","#,
+                             r#""wall_clock_or_standard":"WallClock","local_or_universal_time":"Local"}"#));
+
+    let parsed: ZoneInfoElement = serde_json::from_str(&json).unwrap();
+    assert_eq!((parsed.ut_offset, parsed.isdst, parsed.abbreviation), (7200, true, "CEST".to_string()));
+    assert!(matches!(serde_json::from_str(r#""Universal""#).unwrap(), TransitionTimeFlag::Universal));
 }
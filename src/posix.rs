@@ -0,0 +1,395 @@
+// Parser and evaluator for POSIX TZ strings, as found in the footer of
+// version 2+ zoneinfo files (see tzfile(5) and the TZ variable in POSIX).
+use std::ops::Range;
+use civil;
+use ZoneInfoError;
+
+/// Day of the year on which a daylight saving time rule takes effect
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PosixDate {
+    /// `Jn`: day 1 to 365, February 29th is never counted
+    Julian(u16),
+    /// `n`: zero-based day 0 to 365, February 29th is counted in leap years
+    ZeroBased(u16),
+    /// `Mm.w.d`: day `d` (0 is Sunday) of week `w` (5 is the last week) of
+    /// month `m`
+    MonthWeekDay { month: u8, week: u8, weekday: u8 }
+}
+
+impl PosixDate {
+    /// Returns the month (1 to 12) of this date in a common year
+    pub fn month(&self) -> u32 {
+        let rule = PosixRule { date: *self, time: 0 };
+        let (_, month, _) = civil::civil_from_days(day_of_rule(&rule, 1970));
+
+        month
+    }
+}
+
+/// Moment at which daylight saving time starts or ends
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PosixRule {
+    /// Day of the transition
+    pub date: PosixDate,
+    /// Local time of the transition in seconds after midnight; may be
+    /// negative or exceed a day
+    pub time: i32
+}
+
+/// Daylight saving time part of a POSIX TZ string
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PosixDst {
+    /// Abbreviation during daylight saving time
+    pub abbreviation: String,
+    /// Offset to UTC in seconds during daylight saving time
+    pub ut_offset: i32,
+    /// Start of daylight saving time, in local standard time
+    pub start: PosixRule,
+    /// End of daylight saving time, in local daylight saving time
+    pub end: PosixRule
+}
+
+/// Parsed POSIX TZ string, like `CET-1CEST,M3.5.0,M10.5.0/3`
+///
+/// Offsets are stored like everywhere else in this crate: seconds east of
+/// UTC. Note that POSIX TZ strings use the opposite sign.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PosixTz {
+    /// Abbreviation during standard time
+    pub std_abbreviation: String,
+    /// Offset to UTC in seconds during standard time
+    pub std_ut_offset: i32,
+    /// Daylight saving time rules, if any
+    pub dst: Option<PosixDst>
+}
+
+fn invalid(message: &str) -> ZoneInfoError {
+    ZoneInfoError::InvalidPosixTz(message.to_string())
+}
+
+struct Parser<'a> {
+    spec: &'a [u8],
+    position: usize
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.spec.get(self.position).cloned()
+    }
+
+    fn eat(&mut self, c: u8) -> bool {
+        if self.peek() == Some(c) {
+            self.position += 1;
+            true
+        }
+        else {
+            false
+        }
+    }
+
+    fn at_end(&self) -> bool {
+        self.position == self.spec.len()
+    }
+
+    /// parses an abbreviation; the angle brackets of quoted forms like
+    /// `<+0545>` are not part of the result
+    fn abbreviation(&mut self) -> Result<String, ZoneInfoError> {
+        let start;
+        let end;
+
+        if self.eat(b'<') {
+            start = self.position;
+            while let Some(c) = self.peek() {
+                if !(c.is_ascii_alphanumeric() || c == b'+' || c == b'-') {
+                    break;
+                }
+                self.position += 1;
+            }
+            end = self.position;
+            if !self.eat(b'>') {
+                return Err(invalid("unterminated quoted abbreviation"));
+            }
+        }
+        else {
+            start = self.position;
+            while let Some(c) = self.peek() {
+                if !c.is_ascii_alphabetic() {
+                    break;
+                }
+                self.position += 1;
+            }
+            end = self.position;
+        }
+
+        if end - start < 3 {
+            return Err(invalid("abbreviation shorter than 3 characters"));
+        }
+
+        // only ASCII characters were accepted
+        Ok(String::from_utf8_lossy(&self.spec[start..end]).into_owned())
+    }
+
+    fn number(&mut self, max: u32) -> Result<u32, ZoneInfoError> {
+        let start = self.position;
+        let mut value = 0u32;
+
+        while let Some(c) = self.peek() {
+            if !c.is_ascii_digit() {
+                break;
+            }
+            value = value.saturating_mul(10).saturating_add((c - b'0') as u32);
+            self.position += 1;
+        }
+
+        if self.position == start {
+            Err(invalid("number expected"))
+        }
+        else if value > max {
+            Err(invalid("number out of range"))
+        }
+        else {
+            Ok(value)
+        }
+    }
+
+    /// parses `[+-]hh[:mm[:ss]]` into (signed) seconds
+    fn duration(&mut self, max_hours: u32) -> Result<i32, ZoneInfoError> {
+        let sign = if self.eat(b'-') { -1 } else { self.eat(b'+'); 1 };
+        let mut seconds = self.number(max_hours)? * 3600;
+
+        if self.eat(b':') {
+            seconds += self.number(59)? * 60;
+            if self.eat(b':') {
+                seconds += self.number(59)?;
+            }
+        }
+
+        Ok(sign * seconds as i32)
+    }
+
+    fn starts_duration(&self) -> bool {
+        match self.peek() {
+            Some(c) => c == b'+' || c == b'-' || c.is_ascii_digit(),
+            None => false
+        }
+    }
+
+    fn rule(&mut self) -> Result<PosixRule, ZoneInfoError> {
+        let date = if self.eat(b'J') {
+            let day = self.number(365)?;
+            if day == 0 {
+                return Err(invalid("Julian day out of range"));
+            }
+            PosixDate::Julian(day as u16)
+        }
+        else if self.eat(b'M') {
+            let month = self.number(12)?;
+            if month == 0 || !self.eat(b'.') {
+                return Err(invalid("malformed month rule"));
+            }
+            let week = self.number(5)?;
+            if week == 0 || !self.eat(b'.') {
+                return Err(invalid("malformed month rule"));
+            }
+            let weekday = self.number(6)?;
+            PosixDate::MonthWeekDay { month: month as u8, week: week as u8, weekday: weekday as u8 }
+        }
+        else {
+            PosixDate::ZeroBased(self.number(365)? as u16)
+        };
+
+        // RFC 8536 extends the transition time to -167..167 hours
+        let time = if self.eat(b'/') { self.duration(167)? } else { 7200 };
+
+        Ok(PosixRule { date, time })
+    }
+}
+
+impl PosixTz {
+    /// Parse a POSIX TZ string like `CET-1CEST,M3.5.0,M10.5.0/3`.
+    ///
+    /// When daylight saving time is specified without rules, the US rules
+    /// `M3.2.0,M11.1.0` are assumed.
+    pub fn parse(spec: &str) -> Result<PosixTz, ZoneInfoError> {
+        let mut parser = Parser { spec: spec.as_bytes(), position: 0 };
+
+        let std_abbreviation = parser.abbreviation()?;
+        let std_ut_offset = -parser.duration(24)?;
+
+        let dst = if parser.at_end() {
+            None
+        }
+        else {
+            let abbreviation = parser.abbreviation()?;
+            let ut_offset = if parser.starts_duration() {
+                -parser.duration(24)?
+            }
+            else {
+                std_ut_offset + 3600
+            };
+
+            let (start, end) = if parser.eat(b',') {
+                let start = parser.rule()?;
+                if !parser.eat(b',') {
+                    return Err(invalid("end rule expected"));
+                }
+                (start, parser.rule()?)
+            }
+            else {
+                (PosixRule { date: PosixDate::MonthWeekDay { month: 3, week: 2, weekday: 0 }, time: 7200 },
+                 PosixRule { date: PosixDate::MonthWeekDay { month: 11, week: 1, weekday: 0 }, time: 7200 })
+            };
+
+            Some(PosixDst { abbreviation, ut_offset, start, end })
+        };
+
+        if !parser.at_end() {
+            return Err(invalid("unexpected trailing characters"));
+        }
+
+        Ok(PosixTz { std_abbreviation, std_ut_offset, dst })
+    }
+
+    /// Returns the transitions as (UTC seconds, isdst) taking place in the
+    /// provided range of (UTC) years, ordered by time and without transitions
+    /// which don't change anything (as happens for zones with daylight saving
+    /// time all year).
+    pub fn transitions(&self, years: Range<i32>) -> Vec<(i64, bool)> {
+        transitions_in_years(self, years.start as i64..years.end as i64)
+    }
+
+    /// Tells whether daylight saving time is in effect at the provided
+    /// moment (in seconds since the epoch)
+    pub fn isdst_at(&self, secs: i64) -> bool {
+        let (year, _, _) = civil::civil_from_days(secs.div_euclid(86400));
+
+        self.raw_transitions(year - 1, year + 2)
+            .into_iter()
+            .take_while(|&(time, _)| time <= secs)
+            .last()
+            .is_some_and(|(_, isdst)| isdst)
+    }
+
+    fn raw_transitions(&self, first_year: i64, end_year: i64) -> Vec<(i64, bool)> {
+        let dst = match self.dst {
+            Some(ref dst) => dst,
+            None => return vec![]
+        };
+
+        let mut all = vec![];
+        for year in first_year..end_year {
+            all.extend(rule_seconds(&dst.start, year, self.std_ut_offset).map(|time| (time, true)));
+            all.extend(rule_seconds(&dst.end, year, dst.ut_offset).map(|time| (time, false)));
+        }
+        // stable sort: for equal times the transition of the later rule wins
+        all.sort_by_key(|&(time, _)| time);
+
+        let mut transitions: Vec<(i64, bool)> = vec![];
+        for (time, isdst) in all {
+            if transitions.last().map(|&(t, _)| t) == Some(time) {
+                transitions.pop();
+            }
+            if transitions.last().map(|&(_, d)| d) != Some(isdst) {
+                transitions.push((time, isdst));
+            }
+        }
+
+        transitions
+    }
+}
+
+/// returns the transitions of `PosixTz::transitions` for a range of years
+/// which might not fit an `i32`, like the years of moments close to
+/// `i64::MAX` seconds; transitions beyond the range of `i64` are left out
+pub fn transitions_in_years(tz: &PosixTz, years: Range<i64>) -> Vec<(i64, bool)> {
+    let start = civil::days_from_civil(years.start, 1, 1).saturating_mul(86400);
+    let end = civil::days_from_civil(years.end, 1, 1).saturating_mul(86400);
+
+    // rules of the neighbouring years might cancel out transitions at the
+    // edges of the range
+    tz.raw_transitions(years.start - 1, years.end + 1)
+      .into_iter()
+      .filter(|&(time, _)| time >= start && time < end)
+      .collect()
+}
+
+/// returns the moment (in UTC seconds since the epoch) at which a rule
+/// applies in the provided year, for the offset in effect before it, or
+/// `None` when that moment lies beyond the range of `i64`
+fn rule_seconds(rule: &PosixRule, year: i64, ut_offset: i32) -> Option<i64> {
+    day_of_rule(rule, year).checked_mul(86400)?
+                           .checked_add(rule.time as i64 - ut_offset as i64)
+}
+
+/// returns the day (in days since the epoch) at which a rule applies in the
+/// provided year
+fn day_of_rule(rule: &PosixRule, year: i64) -> i64 {
+    let january1 = civil::days_from_civil(year, 1, 1);
+
+    match rule.date {
+        PosixDate::Julian(day) => {
+            let leap_day = if civil::is_leap_year(year) && day >= 60 { 1 } else { 0 };
+            january1 + day as i64 - 1 + leap_day
+        },
+        PosixDate::ZeroBased(day) => january1 + day as i64,
+        PosixDate::MonthWeekDay { month, week, weekday } => {
+            let first = civil::days_from_civil(year, month as u32, 1);
+            let next = if month == 12 {
+                civil::days_from_civil(year + 1, 1, 1)
+            }
+            else {
+                civil::days_from_civil(year, month as u32 + 1, 1)
+            };
+            // 1970-01-01 was a Thursday
+            let first_weekday = (first + 4).rem_euclid(7);
+            let mut day = first + (weekday as i64 - first_weekday).rem_euclid(7) + 7 * (week as i64 - 1);
+            while day >= next {
+                day -= 7;
+            }
+            day
+        }
+    }
+}
+
+#[test]
+fn posix_parse() {
+    let tz = PosixTz::parse("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+    let dst = tz.dst.unwrap();
+
+    assert_eq!(tz.std_abbreviation, "CET");
+    assert_eq!(tz.std_ut_offset, 3600);
+    assert_eq!(dst.abbreviation, "CEST");
+    assert_eq!(dst.ut_offset, 7200);
+    assert_eq!(dst.start, PosixRule { date: PosixDate::MonthWeekDay { month: 3, week: 5, weekday: 0 }, time: 7200 });
+    assert_eq!(dst.end, PosixRule { date: PosixDate::MonthWeekDay { month: 10, week: 5, weekday: 0 }, time: 10800 });
+
+    let tz = PosixTz::parse("<-03>3<-02>,M3.5.0/-2,M10.5.0/-1").unwrap();
+    assert_eq!(tz.std_abbreviation, "-03");
+    assert_eq!(tz.std_ut_offset, -10800);
+    assert_eq!(tz.dst.unwrap().start.time, -7200);
+
+    assert!(PosixTz::parse("UTC0").unwrap().dst.is_none());
+    assert!(PosixTz::parse("").is_err());
+    assert!(PosixTz::parse("CET-1CEST,M3.5.0").is_err());
+    assert!(PosixTz::parse("CET-1CEST,M13.5.0,M10.5.0/3").is_err());
+    assert!(PosixTz::parse("CET-1 ").is_err());
+}
+
+#[test]
+fn posix_transitions() {
+    let tz = PosixTz::parse("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+    let march28 = civil::days_from_civil(2021, 3, 28) * 86400;
+    let october31 = civil::days_from_civil(2021, 10, 31) * 86400;
+
+    assert_eq!(tz.transitions(2021..2022), vec![(march28 + 3600, true), (october31 + 3600, false)]);
+
+    // permanent daylight saving time
+    let tz = PosixTz::parse("EST5EDT,0/0,J365/25").unwrap();
+    assert_eq!(tz.transitions(2021..2024).len(), 0);
+    assert!(tz.isdst_at(civil::days_from_civil(2022, 1, 1) * 86400));
+    assert!(tz.isdst_at(civil::days_from_civil(2022, 12, 31) * 86400));
+
+    let tz = PosixTz::parse("AEST-10AEDT,M10.1.0,M4.1.0/3").unwrap();
+    assert!(tz.isdst_at(civil::days_from_civil(2022, 1, 1) * 86400));
+    assert!(!tz.isdst_at(civil::days_from_civil(2022, 7, 1) * 86400));
+}
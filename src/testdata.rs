@@ -0,0 +1,92 @@
+// Helpers to craft tzfile(5) contents for the unit tests, so tests don't
+// depend on whatever zoneinfo data happens to be installed.
+use byteorder::{BigEndian, WriteBytesExt};
+
+/// one data block of a tzfile, including the header counts derived from it
+#[derive(Debug, Clone, Default)]
+pub struct Block {
+    pub times: Vec<i64>,
+    pub types: Vec<u8>,
+    pub ttinfos: Vec<(i32, bool, u8)>, // (ut_offset, isdst, abbr_index)
+    pub chars: Vec<u8>,
+    pub leaps: Vec<(i64, i32)>,
+    pub isstd: Vec<u8>,
+    pub isut: Vec<u8>,
+}
+
+impl Block {
+    /// builds a block out of (ut_offset, isdst, abbreviation) types and
+    /// (transition time, type index) transitions
+    pub fn new(types: &[(i32, bool, &str)], transitions: &[(i64, u8)]) -> Block {
+        let mut block = Block::default();
+
+        for &(ut_offset, isdst, abbreviation) in types {
+            let index = block.chars.len() as u8;
+            block.chars.extend(abbreviation.bytes());
+            block.chars.push(0);
+            block.ttinfos.push((ut_offset, isdst, index));
+        }
+
+        for &(time, index) in transitions {
+            block.times.push(time);
+            block.types.push(index);
+        }
+
+        block
+    }
+
+    fn write(&self, out: &mut Vec<u8>, version: u8, wide: bool) {
+        out.extend(b"TZif");
+        out.push(version);
+        out.extend(&[0u8; 15]);
+        for &count in &[self.isut.len(), self.isstd.len(), self.leaps.len(),
+                        self.times.len(), self.ttinfos.len(), self.chars.len()] {
+            out.write_u32::<BigEndian>(count as u32).unwrap();
+        }
+
+        for &time in &self.times {
+            write_time(out, time, wide);
+        }
+        out.extend(&self.types);
+        for &(ut_offset, isdst, abbr_index) in &self.ttinfos {
+            out.write_i32::<BigEndian>(ut_offset).unwrap();
+            out.push(isdst as u8);
+            out.push(abbr_index);
+        }
+        out.extend(&self.chars);
+        for &(time, correction) in &self.leaps {
+            write_time(out, time, wide);
+            out.write_i32::<BigEndian>(correction).unwrap();
+        }
+        out.extend(&self.isstd);
+        out.extend(&self.isut);
+    }
+}
+
+fn write_time(out: &mut Vec<u8>, time: i64, wide: bool) {
+    if wide {
+        out.write_i64::<BigEndian>(time).unwrap();
+    }
+    else {
+        out.write_i32::<BigEndian>(time as i32).unwrap();
+    }
+}
+
+/// returns a version 1 tzfile consisting of a single 32-bit block
+pub fn tzif_v1(block: &Block) -> Vec<u8> {
+    let mut out = vec![];
+    block.write(&mut out, 0, false);
+    out
+}
+
+/// returns a version 2+ tzfile with a 32-bit block, a 64-bit block and a TZ
+/// string footer (`tail` is written between the mandatory newlines)
+pub fn tzif(version: u8, v1: &Block, v2: &Block, tail: &str) -> Vec<u8> {
+    let mut out = vec![];
+    v1.write(&mut out, version, false);
+    v2.write(&mut out, version, true);
+    out.push(b'\n');
+    out.extend(tail.bytes());
+    out.push(b'\n');
+    out
+}
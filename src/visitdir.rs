@@ -5,16 +5,16 @@ use std::fs::{self, DirEntry, metadata};
 use std::path::Path;
 
 // one possible implementation of fs::walk_dir only visiting files
-pub fn visit_dirs(dir: &Path, cb: &mut FnMut(DirEntry)) -> io::Result<()> {
-    let meta = try!(metadata(dir));
+pub fn visit_dirs(dir: &Path, cb: &mut dyn FnMut(DirEntry)) -> io::Result<()> {
+    let meta = metadata(dir)?;
     // if dir.is_dir() {
     if meta.is_dir() {
-        for entry in try!(fs::read_dir(dir)) {
-            let entry = try!(entry);
-            let meta = try!(metadata(entry.path()));
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let meta = metadata(entry.path())?;
             //if entry.path().is_dir() {
             if meta.is_dir() {
-                try!(visit_dirs(&entry.path(), cb));
+                visit_dirs(&entry.path(), cb)?;
             } else {
                 cb(entry);
             }